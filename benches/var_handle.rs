@@ -0,0 +1,44 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ir_telemetry::synthetic::SyntheticBuilder;
+use ir_telemetry::vars;
+
+fn reads(c: &mut Criterion) {
+    let telemetry = SyntheticBuilder::new(60)
+        .var("Speed", "m/s", "GPS vehicle speed", |tick| tick as f32)
+        .array("CarIdxLapDistPct", 64, "%", "Lap distance", |_, i| {
+            i as f32 / 64.
+        })
+        .build();
+    let var_headers = telemetry.var_headers();
+    let data = telemetry.data_at(0);
+    let speed_header = var_headers.get("Speed");
+    let lap_dist_header = var_headers.get("CarIdxLapDistPct");
+    let speed = vars::SPEED.handle(&var_headers).unwrap();
+    let lap_dist = vars::CAR_IDX_LAP_DIST_PCT.handle(&var_headers).unwrap();
+
+    let mut group = c.benchmark_group("scalar");
+    group.bench_function("get", |b| {
+        b.iter(|| black_box(&data).get::<f32>(speed_header))
+    });
+    group.bench_function("handle", |b| b.iter(|| speed.read(black_box(&data))));
+    group.finish();
+
+    let mut group = c.benchmark_group("array");
+    group.bench_function("get", |b| {
+        b.iter(|| black_box(&data).get::<Vec<f32>>(lap_dist_header))
+    });
+    group.bench_function("handle_to_vec", |b| {
+        b.iter(|| lap_dist.to_vec(black_box(&data)))
+    });
+    let mut values = [0f32; 64];
+    group.bench_function("handle_into", |b| {
+        b.iter(|| lap_dist.read_into(black_box(&data), &mut values))
+    });
+    group.bench_function("handle_array", |b| {
+        b.iter(|| lap_dist.read_array::<64>(black_box(&data)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, reads);
+criterion_main!(benches);
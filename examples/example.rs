@@ -5,10 +5,15 @@ use session_info::session;
 
 fn main() {
     pretty_env_logger::init();
-    println!("Starting iRacing Telemetry.  Ensure iRacing is running");
 
-    // Request updates two times a second. Can do up to 60 updates/ second
-    let rx = client::Client::connect(2.);
+    // Pass the path of a dump file to read it instead of the live memory mapped file.
+    let rx = match std::env::args().nth(1) {
+        Some(path) => {
+            println!("Reading dump file {}", path);
+            client::Client::connect_with(2., move || DumpFile::open(&path))
+        }
+        None => connect_live(),
+    };
 
     let mut var_headers = std::collections::HashMap::new();
 
@@ -17,7 +22,7 @@ fn main() {
             UpdatePacket::Data(data) => handle_data_update(&data, &var_headers),
             // Not expected to happen often, but when session data is updated, this will be recieved
             UpdatePacket::SessionInfo(session) => handle_session_update(&session),
-            UpdatePacket::NotConnected => println!("Session is not open"),
+            UpdatePacket::Disconnected { reason } => println!("Not connected: {}", reason),
             UpdatePacket::VariableHeaders(v_header) => {
                 println!("# of variables in this session: {:?}", v_header.len());
                 var_headers = v_header;
@@ -27,6 +32,20 @@ fn main() {
     }
 }
 
+#[cfg(windows)]
+fn connect_live() -> ClientHandle {
+    println!("Starting iRacing Telemetry.  Ensure iRacing is running");
+
+    // Request updates two times a second. Can do up to 60 updates/ second
+    client::Client::connect(2.)
+}
+
+#[cfg(not(windows))]
+fn connect_live() -> ClientHandle {
+    eprintln!("Live telemetry requires Windows.  Pass the path of a dump file instead.");
+    std::process::exit(1);
+}
+
 fn handle_data_update(
     data: &IrData,
     var_headers: &std::collections::HashMap<String, var_header::VarHeader>,
@@ -49,7 +68,7 @@ fn handle_data_update(
     println!("Track locations: {}", track_location);
 }
 
-fn handle_session_update(session: &String) {
+fn handle_session_update(session: &str) {
     let session: session::Session = serde_yaml::from_str(session).unwrap();
     let my_index = session.driver_info.driver_car_idx;
     let my_driver = &session.driver_info.drivers[my_index as usize];
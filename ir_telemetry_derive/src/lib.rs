@@ -0,0 +1,179 @@
+//! # iRacing Telemetry derive
+//! `#[derive(Telemetry)]` for `ir_telemetry`.  Use it through the `derive` feature of `ir_telemetry`, which
+//! re-exports the macro next to the `Telemetry` trait.
+//!
+//! Each field is read from the variable matching its name, ignoring case and underscores, so `lap_dist_pct` reads
+//! `LapDistPct` and `rpm` reads `RPM`.  A name matching more than one variable this way is an error, unless one of
+//! them is named exactly like the field.  `#[telemetry(name = "...")]` names the variable exactly instead.
+//! Fields are any type implementing `TelemetryField`: the types of `vars::VarValue` for single variables, `Vec` of
+//! them for arrays, and `Option` of either for variables missing from some sessions.  `Option<Duration>` is the
+//! exception: it reads lap times that are not set as `None`, see `OptionalField`.
+//!
+//! The macro generates a `<Struct>Resolver` holding the variable headers of the fields, built once per session with
+//! `Telemetry::resolver` or `<Struct>Resolver::new`, which fails if a variable is missing or has the wrong type.
+//! Reading with the resolver only copies the values out of the buffer.
+//! ```
+//! use ir_telemetry::synthetic::SyntheticBuilder;
+//! use ir_telemetry::Telemetry;
+//!
+//! #[derive(Debug, Telemetry)]
+//! struct Inputs {
+//!     throttle: f32,
+//!     brake: f32,
+//!     #[telemetry(name = "SteeringWheelAngle")]
+//!     steering: f32,
+//!     gear: Option<i32>,
+//! }
+//!
+//! let telemetry = SyntheticBuilder::new(60)
+//!     .var("Throttle", "%", "Throttle", |_| 1.0f32)
+//!     .var("Brake", "%", "Brake", |_| 0.0f32)
+//!     .var("SteeringWheelAngle", "rad", "Steering wheel angle", |_| 0.5f32)
+//!     .build();
+//! let inputs = Inputs::extract(&telemetry.data_at(0)).unwrap();
+//! assert_eq!(inputs.steering, 0.5);
+//! assert_eq!(inputs.gear, None);
+//!
+//! // Resolve once per session, then read every update.
+//! let resolver = InputsResolver::new(&telemetry.var_headers()).unwrap();
+//! let inputs = resolver.extract(&telemetry.data_at(1)).unwrap();
+//! assert_eq!(inputs.throttle, 1.0);
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr, Result};
+
+/// Reads the variables named by the fields of a struct.  See the crate documentation.
+#[proc_macro_derive(Telemetry, attributes(telemetry))]
+pub fn derive_telemetry(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// A field and the variable it is read from.
+struct Field {
+    ident: syn::Ident,
+    ty: syn::Type,
+    name: String,
+    exact: bool,
+}
+
+impl Field {
+    fn parse(field: &syn::Field) -> Result<Self> {
+        // Only named fields reach here.
+        let ident = field.ident.clone().unwrap();
+        let mut name = None;
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|a| a.path().is_ident("telemetry"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    name = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `name = \"...\"`"))
+                }
+            })?;
+        }
+        Ok(Self {
+            exact: name.is_some(),
+            name: name.unwrap_or_else(|| ident.to_string().trim_start_matches("r#").to_string()),
+            ident,
+            ty: field.ty.clone(),
+        })
+    }
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    let ident = &input.ident;
+    let vis = &input.vis;
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "Telemetry can not be derived for generic structs",
+        ));
+    }
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &data.fields,
+                    "Telemetry can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "Telemetry can only be derived for structs",
+            ))
+        }
+    };
+    let fields = fields
+        .iter()
+        .map(Field::parse)
+        .collect::<Result<Vec<_>>>()?;
+
+    let resolver = format_ident!("{}Resolver", ident);
+    let resolver_doc = format!("The variables of `{}`, resolved for a session.", ident);
+    let idents = fields.iter().map(|f| &f.ident).collect::<Vec<_>>();
+    let types = fields.iter().map(|f| &f.ty).collect::<Vec<_>>();
+    let names = fields.iter().map(|f| &f.name).collect::<Vec<_>>();
+    let exacts = fields.iter().map(|f| f.exact).collect::<Vec<_>>();
+
+    Ok(quote! {
+        #[doc = #resolver_doc]
+        #[derive(Debug, Clone)]
+        #vis struct #resolver {
+            #(#idents: <#types as ::ir_telemetry::TelemetryField>::Variable,)*
+        }
+
+        impl #resolver {
+            /// Looks up the variable of each field in the variable headers of a session.
+            #vis fn new(
+                var_headers: &::std::collections::HashMap<::std::string::String, ::ir_telemetry::VarHeader>,
+            ) -> ::std::result::Result<Self, ::ir_telemetry::IrError> {
+                ::std::result::Result::Ok(Self {
+                    #(#idents: <#types as ::ir_telemetry::TelemetryField>::resolve(
+                        #names,
+                        ::ir_telemetry::telemetry::find_variable(var_headers, #names, #exacts)?,
+                    )?,)*
+                })
+            }
+
+            /// Reads the fields from data of the session the resolver was created for.
+            #vis fn extract(
+                &self,
+                data: &::ir_telemetry::IrData,
+            ) -> ::std::result::Result<#ident, ::ir_telemetry::IrError> {
+                ::std::result::Result::Ok(#ident {
+                    #(#idents: <#types as ::ir_telemetry::TelemetryField>::read(&self.#idents, data)?,)*
+                })
+            }
+        }
+
+        impl ::ir_telemetry::Telemetry for #ident {
+            type Resolver = #resolver;
+
+            fn resolver(
+                var_headers: &::std::collections::HashMap<::std::string::String, ::ir_telemetry::VarHeader>,
+            ) -> ::std::result::Result<Self::Resolver, ::ir_telemetry::IrError> {
+                #resolver::new(var_headers)
+            }
+
+            fn extract_with(
+                resolver: &Self::Resolver,
+                data: &::ir_telemetry::IrData,
+            ) -> ::std::result::Result<Self, ::ir_telemetry::IrError> {
+                resolver.extract(data)
+            }
+        }
+    })
+}
@@ -0,0 +1,64 @@
+use ir_telemetry::synthetic::{SyntheticBuilder, SyntheticTelemetry};
+use ir_telemetry::{IrError, SessionState, Telemetry};
+
+#[derive(Debug, Telemetry)]
+pub struct Car {
+    speed: f32,
+    rpm: f32,
+    session_state: SessionState,
+    #[telemetry(name = "CarIdxLapDistPct")]
+    lap_dist_pct: Vec<f32>,
+    gear: Option<i32>,
+}
+
+fn telemetry() -> SyntheticTelemetry {
+    SyntheticBuilder::new(60)
+        .var("Speed", "m/s", "GPS vehicle speed", |tick| tick as f32)
+        .var("RPM", "revs/min", "Engine rpm", |_| 7500f32)
+        .var("SessionState", "irsdk_SessionState", "Session state", |_| 4)
+        .array("CarIdxLapDistPct", 2, "%", "Lap distance", |_, i| i as f32)
+        .build()
+}
+
+#[test]
+fn test_extract_fields() {
+    let telemetry = telemetry();
+    let car = Car::extract(&telemetry.data_at(3)).unwrap();
+    assert_eq!(car.speed, 3.);
+    assert_eq!(car.rpm, 7500.);
+    assert_eq!(car.session_state, SessionState::Racing);
+    assert_eq!(car.lap_dist_pct, vec![0., 1.]);
+    assert_eq!(car.gear, None);
+
+    let resolver = Car::resolver(&telemetry.var_headers()).unwrap();
+    for tick in 0..10 {
+        let car = resolver.extract(&telemetry.data_at(tick)).unwrap();
+        assert_eq!(car.speed, tick as f32);
+    }
+}
+
+#[test]
+fn test_missing_and_mistyped_variables() {
+    let missing = SyntheticBuilder::new(60)
+        .var("Speed", "m/s", "GPS vehicle speed", |_| 0f32)
+        .build();
+    assert!(matches!(
+        CarResolver::new(&missing.var_headers()),
+        Err(IrError::VariableNotFound(ref name)) if name == "rpm"
+    ));
+
+    let mistyped = telemetry().var_headers();
+    #[derive(Debug, Telemetry)]
+    struct Mistyped {
+        #[allow(dead_code)]
+        car_idx_lap_dist_pct: f32,
+    }
+    let error = Mistyped::resolver(&mistyped).unwrap_err();
+    assert!(
+        matches!(error, IrError::MismatchedType { ref name, .. } if name == "CarIdxLapDistPct")
+    );
+    assert_eq!(
+        error.to_string(),
+        "Variable CarIdxLapDistPct is [Float; 2], which can not be read as f32"
+    );
+}
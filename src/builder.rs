@@ -0,0 +1,347 @@
+use std::time::Duration;
+
+use crate::channel::{channel, Backpressure};
+use crate::client::{ClientHandle, ClientInner, DisconnectReason, UpdatePacket};
+use crate::diagnostics::Diagnostics;
+use crate::error::{IrError, Result};
+#[cfg(windows)]
+use crate::mapped_file::memfile::FileMap;
+use crate::source::TelemetrySource;
+
+/// How long the client waits before opening the source again, after it failed to open or the session ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    factor: u32,
+}
+
+impl Backoff {
+    /// Waits the same time between each attempt.
+    pub fn fixed(delay: Duration) -> Self {
+        Self {
+            initial: delay,
+            max: delay,
+            factor: 1,
+        }
+    }
+
+    /// Doubles the wait after each failed attempt, starting at `initial` and up to `max`.
+    pub fn exponential(initial: Duration, max: Duration) -> Self {
+        Self {
+            initial,
+            max: max.max(initial),
+            factor: 2,
+        }
+    }
+
+    /// The wait before retry number `retry`, counting from zero.
+    pub fn delay(&self, retry: u32) -> Duration {
+        self.initial
+            .saturating_mul(self.factor.saturating_pow(retry))
+            .min(self.max)
+    }
+}
+
+impl Default for Backoff {
+    /// Ten seconds between attempts.
+    fn default() -> Self {
+        Self::fixed(Duration::from_secs(10))
+    }
+}
+
+/// Which ticks the client reads at each update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CaptureMode {
+    /// Only the most recent tick.  Ticks in between updates are not sent.
+    #[default]
+    Latest,
+    /// Every tick written since the last update, in tick order.  The sim only keeps the last few ticks, so with an
+    /// update rate well below the tick rate, or a source that falls behind, some are overwritten before they are read.
+    /// Those are reported with `UpdatePacket::SkippedTicks` before the next data packet.
+    EveryTick,
+}
+
+/// The slowest update rate, one update every 100 seconds.
+const MIN_UPDATE_RATE: f32 = 0.01;
+
+type Open<S> = Box<dyn FnMut() -> Result<S> + Send>;
+
+/// Configures and starts a `Client`.
+/// ```
+/// use std::time::Duration;
+/// use ir_telemetry::{Backoff, Backpressure, ClientBuilder, DumpFile};
+///
+/// let rx = ClientBuilder::with_source(|| DumpFile::open("iracing_dumpfile.dat"))
+///     .update_rate(30.)
+///     .capacity(8)
+///     .backpressure(Backpressure::LatestOnly)
+///     .backoff(Backoff::exponential(Duration::from_millis(500), Duration::from_secs(10)))
+///     .connect();
+/// # drop(rx);
+/// ```
+pub struct ClientBuilder<S> {
+    open: Open<S>,
+    update_rate: f32,
+    capacity: usize,
+    backpressure: Backpressure,
+    backoff: Backoff,
+    capture: CaptureMode,
+    diagnostics: Diagnostics,
+}
+
+#[cfg(windows)]
+impl ClientBuilder<FileMap> {
+    /// A builder for the live memory mapped file of iRacing.
+    pub fn new() -> Self {
+        Self::with_source(FileMap::new)
+    }
+}
+
+#[cfg(windows)]
+impl Default for ClientBuilder<FileMap> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: TelemetrySource + 'static> ClientBuilder<S> {
+    /// A builder for any `TelemetrySource`.  `open` is called on the client thread each time a connection is
+    /// (re)established, so the source itself does not need to be `Send`.
+    pub fn with_source<F>(open: F) -> Self
+    where
+        F: FnMut() -> Result<S> + Send + 'static,
+    {
+        Self {
+            open: Box::new(open),
+            update_rate: 60.,
+            capacity: 4,
+            backpressure: Backpressure::default(),
+            backoff: Backoff::default(),
+            capture: CaptureMode::default(),
+            diagnostics: Diagnostics::default(),
+        }
+    }
+
+    /// Replaces the source, keeping the other settings.
+    pub fn source<T, F>(self, open: F) -> ClientBuilder<T>
+    where
+        F: FnMut() -> Result<T> + Send + 'static,
+    {
+        ClientBuilder {
+            open: Box::new(open),
+            update_rate: self.update_rate,
+            capacity: self.capacity,
+            backpressure: self.backpressure,
+            backoff: self.backoff,
+            capture: self.capture,
+            diagnostics: self.diagnostics,
+        }
+    }
+
+    /// Data updates per second.  Can be up to the tick rate of the source, 60 for iRacing.  Defaults to 60.  Rates
+    /// below one update every 100 seconds, including zero and negative rates, are raised to it, and a rate that is not
+    /// a number keeps the current rate.
+    pub fn update_rate(mut self, update_rate: f32) -> Self {
+        if !update_rate.is_nan() {
+            self.update_rate = update_rate.max(MIN_UPDATE_RATE);
+        }
+        self
+    }
+
+    /// Number of packets queued for the receiver before `backpressure` applies.  Defaults to 4.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// What to do when the receiver falls behind.  Defaults to `Backpressure::Block`.
+    pub fn backpressure(mut self, backpressure: Backpressure) -> Self {
+        self.backpressure = backpressure;
+        self
+    }
+
+    /// The wait between attempts to open the source.  Defaults to ten seconds.
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Which ticks are read at each update.  Defaults to `CaptureMode::Latest`.
+    pub fn capture(mut self, capture: CaptureMode) -> Self {
+        self.capture = capture;
+        self
+    }
+
+    /// Counts packets dropped by backpressure and ticks skipped with `CaptureMode::EveryTick`.
+    pub fn diagnostics(mut self, diagnostics: Diagnostics) -> Self {
+        self.diagnostics = diagnostics;
+        self
+    }
+
+    /// Starts the client thread.
+    pub fn connect(self) -> ClientHandle {
+        let Self {
+            mut open,
+            update_rate,
+            capacity,
+            backpressure,
+            backoff,
+            capture,
+            diagnostics,
+        } = self;
+        let (tx, rx) = channel(capacity, backpressure, diagnostics.clone());
+
+        let worker = std::thread::spawn(move || {
+            let mut retries = 0;
+            let mut announced = false;
+            // The error that ended the last session or attempt to open the source, returned by `join`.
+            let mut last_error = None;
+            while !tx.is_closed() {
+                let delay = match open() {
+                    Ok(connection) => {
+                        log::info!("Connected to telemetry source");
+                        retries = 0;
+                        announced = false;
+                        // The source is dropped, and its handles released, when the session ends.
+                        last_error = match ClientInner::new(update_rate, connection, tx.clone())
+                            .capture(capture, diagnostics.clone())
+                            .start()
+                        {
+                            Err(IrError::ChannelClosed) => break,
+                            Err(e) => Some(e),
+                            Ok(()) => None,
+                        };
+                        log::warn!("Waiting for iRacing session to load");
+                        backoff.delay(0)
+                    }
+                    Err(e) => {
+                        log::debug!("Waiting for iRacing to start: {}", e);
+                        last_error = Some(e);
+                        if !announced {
+                            announced = true;
+                            let reason = DisconnectReason::SimNotRunning;
+                            if tx.send(UpdatePacket::Disconnected { reason }).is_err() {
+                                break;
+                            }
+                        }
+                        let delay = backoff.delay(retries);
+                        retries = retries.saturating_add(1);
+                        delay
+                    }
+                };
+                if tx.wait_closed(delay) {
+                    break;
+                }
+            }
+            log::debug!("Client stopped");
+            last_error.map_or(Ok(()), Err)
+        });
+
+        ClientHandle::new(rx, worker)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synthetic::SyntheticBuilder;
+
+    #[test]
+    fn test_backoff_delays() {
+        let fixed = Backoff::fixed(Duration::from_secs(2));
+        assert_eq!(fixed.delay(0), Duration::from_secs(2));
+        assert_eq!(fixed.delay(10), Duration::from_secs(2));
+
+        let exponential = Backoff::exponential(Duration::from_millis(100), Duration::from_secs(1));
+        assert_eq!(exponential.delay(0), Duration::from_millis(100));
+        assert_eq!(exponential.delay(2), Duration::from_millis(400));
+        assert_eq!(exponential.delay(4), Duration::from_secs(1));
+        assert_eq!(exponential.delay(u32::MAX), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_invalid_update_rates_are_clamped() {
+        let builder =
+            || ClientBuilder::with_source(|| Ok(SyntheticBuilder::new(60).build().source()));
+        assert_eq!(builder().update_rate(0.).update_rate, MIN_UPDATE_RATE);
+        assert_eq!(builder().update_rate(-5.).update_rate, MIN_UPDATE_RATE);
+        assert_eq!(builder().update_rate(f32::NAN).update_rate, 60.);
+        assert_eq!(builder().update_rate(30.).update_rate, 30.);
+
+        // Used to divide by zero.
+        let (tx, _rx) = channel(1, Backpressure::default(), Diagnostics::default());
+        let source = SyntheticBuilder::new(60).build().source();
+        ClientInner::new(builder().update_rate(0.).update_rate, source, tx);
+    }
+
+    #[test]
+    fn test_latest_only_drops_stale_data() {
+        let telemetry = SyntheticBuilder::new(60).session_clock().build();
+        let diagnostics = Diagnostics::new();
+        let rx = ClientBuilder::with_source(move || Ok(telemetry.source()))
+            .capacity(1)
+            .backpressure(Backpressure::LatestOnly)
+            .diagnostics(diagnostics.clone())
+            .connect();
+
+        assert!(matches!(rx.recv(), Ok(UpdatePacket::Connected { .. })));
+        assert!(matches!(rx.recv(), Ok(UpdatePacket::VariableHeaders(_))));
+        assert!(matches!(rx.recv(), Ok(UpdatePacket::SessionInfo(_))));
+        // Fall behind the client.
+        std::thread::sleep(Duration::from_millis(200));
+        let queued = rx.try_iter().count();
+        assert_eq!(queued, 1);
+        assert!(diagnostics.dropped_packets() > 0);
+    }
+
+    #[test]
+    fn test_every_tick_reports_skipped_ticks() {
+        let telemetry = SyntheticBuilder::new(60).session_clock().build();
+        let diagnostics = Diagnostics::new();
+        // Ten ticks per update, of which the source keeps three.
+        let rx = ClientBuilder::with_source(move || Ok(telemetry.source()))
+            .update_rate(6.)
+            .capacity(64)
+            .capture(CaptureMode::EveryTick)
+            .diagnostics(diagnostics.clone())
+            .connect();
+        std::thread::sleep(Duration::from_millis(600));
+        rx.stop();
+
+        let mut last_tick = None;
+        let mut skipped = 0;
+        for packet in rx.iter() {
+            match packet {
+                UpdatePacket::SkippedTicks(count) => skipped += count as i32,
+                UpdatePacket::Data(data) => {
+                    if let Some(last_tick) = last_tick {
+                        assert_eq!(data.tick(), last_tick + skipped + 1);
+                    }
+                    last_tick = Some(data.tick());
+                    skipped = 0;
+                }
+                _ => (),
+            }
+        }
+        assert!(last_tick.is_some());
+        assert!(diagnostics.skipped_ticks() > 0);
+    }
+
+    #[test]
+    fn test_sim_not_running_is_sent_once() {
+        let rx = ClientBuilder::<crate::synthetic::SyntheticSource>::with_source(|| {
+            Err(crate::error::IrError::MemMappingError("no sim".into()))
+        })
+        .backoff(Backoff::fixed(Duration::from_millis(5)))
+        .connect();
+
+        assert!(matches!(
+            rx.recv(),
+            Ok(UpdatePacket::Disconnected {
+                reason: DisconnectReason::SimNotRunning
+            })
+        ));
+        assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+    }
+}
@@ -0,0 +1,452 @@
+use std::collections::VecDeque;
+use std::sync::mpsc::{RecvError, RecvTimeoutError, SendError, TryRecvError};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+#[cfg(feature = "async")]
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+use crate::client::UpdatePacket;
+use crate::diagnostics::Diagnostics;
+
+/// What the client does with new data when the channel is full because the receiver is not keeping up.
+/// Only `UpdatePacket::Data` is ever dropped, session info, variable headers and connection changes are always
+/// delivered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backpressure {
+    /// Wait for the receiver to make room.  Nothing is dropped, but the client falls behind the sim.
+    #[default]
+    Block,
+    /// Drop the oldest queued data to make room for the new data.
+    DropOldest,
+    /// Drop the new data.
+    DropNewest,
+    /// Keep only the most recent data.  Queued data is replaced by new data, regardless of the capacity.
+    LatestOnly,
+}
+
+/// Packets that may be dropped under backpressure.
+fn is_droppable(packet: &UpdatePacket) -> bool {
+    matches!(packet, UpdatePacket::Data(_))
+}
+
+#[derive(Debug)]
+struct State {
+    queue: VecDeque<UpdatePacket>,
+    senders: usize,
+    /// False once the receiver was dropped or the client was stopped.
+    open: bool,
+    /// Woken when a packet is queued or the last sender is dropped, for async receivers.
+    #[cfg(feature = "async")]
+    waker: Option<Waker>,
+}
+
+impl State {
+    fn wake(&mut self) {
+        #[cfg(feature = "async")]
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Shared {
+    state: Mutex<State>,
+    capacity: usize,
+    backpressure: Backpressure,
+    diagnostics: Diagnostics,
+    not_empty: Condvar,
+    not_full: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // The lock is never held while calling user code, a poisoned lock still holds a consistent queue.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Creates a bounded channel of `UpdatePacket` that applies `backpressure` when `capacity` packets are queued.
+/// Dropped packets are counted in `diagnostics`.
+pub(crate) fn channel(
+    capacity: usize,
+    backpressure: Backpressure,
+    diagnostics: Diagnostics,
+) -> (Sender, Receiver) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            queue: VecDeque::with_capacity(capacity),
+            senders: 1,
+            open: true,
+            #[cfg(feature = "async")]
+            waker: None,
+        }),
+        capacity: capacity.max(1),
+        backpressure,
+        diagnostics,
+        not_empty: Condvar::new(),
+        not_full: Condvar::new(),
+    });
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared },
+    )
+}
+
+/// The sending half of the channel, used by the client thread.
+#[derive(Debug)]
+pub(crate) struct Sender {
+    shared: Arc<Shared>,
+}
+
+impl Sender {
+    /// Queues a packet, applying the backpressure mode.  Fails, dropping the packet, if the receiver was dropped.
+    pub(crate) fn send(&self, packet: UpdatePacket) -> Result<(), SendError<()>> {
+        let shared = &self.shared;
+        let mut state = shared.lock();
+        if !state.open {
+            return Err(SendError(()));
+        }
+        if is_droppable(&packet) {
+            match shared.backpressure {
+                Backpressure::Block => {
+                    while state.open && state.queue.len() >= shared.capacity {
+                        state = shared
+                            .not_full
+                            .wait(state)
+                            .unwrap_or_else(|e| e.into_inner());
+                    }
+                    if !state.open {
+                        return Err(SendError(()));
+                    }
+                }
+                Backpressure::DropOldest if state.queue.len() >= shared.capacity => {
+                    if let Some(oldest) = state.queue.iter().position(is_droppable) {
+                        state.queue.remove(oldest);
+                        shared.diagnostics.record_dropped_packets(1);
+                    }
+                }
+                Backpressure::DropNewest if state.queue.len() >= shared.capacity => {
+                    shared.diagnostics.record_dropped_packets(1);
+                    return Ok(());
+                }
+                Backpressure::LatestOnly => {
+                    let queued = state.queue.len();
+                    state.queue.retain(|p| !is_droppable(p));
+                    shared
+                        .diagnostics
+                        .record_dropped_packets((queued - state.queue.len()) as u64);
+                }
+                Backpressure::DropOldest | Backpressure::DropNewest => (),
+            }
+        }
+        state.queue.push_back(packet);
+        state.wake();
+        shared.not_empty.notify_one();
+        Ok(())
+    }
+
+    /// Returns true once the receiver was dropped or the client was stopped.
+    pub(crate) fn is_closed(&self) -> bool {
+        !self.shared.lock().open
+    }
+
+    /// Waits for the timeout, returning early with true if the channel is closed.
+    pub(crate) fn wait_closed(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.shared.lock();
+        while state.open {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return false;
+            }
+            state = self
+                .shared
+                .not_full
+                .wait_timeout(state, remaining)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+        true
+    }
+}
+
+impl Clone for Sender {
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl Drop for Sender {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.senders -= 1;
+        if state.senders == 0 {
+            state.wake();
+        }
+        self.shared.not_empty.notify_all();
+    }
+}
+
+/// The receiving half of the channel the client sends `UpdatePacket` over.  It mirrors the api of
+/// `std::sync::mpsc::Receiver`.  Dropping the receiver stops the client.
+#[derive(Debug)]
+pub struct Receiver {
+    shared: Arc<Shared>,
+}
+
+impl Receiver {
+    fn take(&self, state: &mut State) -> Option<UpdatePacket> {
+        let packet = state.queue.pop_front();
+        if packet.is_some() {
+            self.shared.not_full.notify_one();
+        }
+        packet
+    }
+
+    /// Blocks until a packet is received.  Fails once the client has stopped and all packets are received.
+    pub fn recv(&self) -> Result<UpdatePacket, RecvError> {
+        let mut state = self.shared.lock();
+        loop {
+            if let Some(packet) = self.take(&mut state) {
+                return Ok(packet);
+            }
+            if state.senders == 0 {
+                return Err(RecvError);
+            }
+            state = self
+                .shared
+                .not_empty
+                .wait(state)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Returns a packet if one is queued, without blocking.
+    pub fn try_recv(&self) -> Result<UpdatePacket, TryRecvError> {
+        let mut state = self.shared.lock();
+        match self.take(&mut state) {
+            Some(packet) => Ok(packet),
+            None if state.senders == 0 => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Blocks until a packet is received or the timeout expires.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<UpdatePacket, RecvTimeoutError> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.shared.lock();
+        loop {
+            if let Some(packet) = self.take(&mut state) {
+                return Ok(packet);
+            }
+            if state.senders == 0 {
+                return Err(RecvTimeoutError::Disconnected);
+            }
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(RecvTimeoutError::Timeout);
+            }
+            state = self
+                .shared
+                .not_empty
+                .wait_timeout(state, remaining)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+
+    /// Polls for a packet, registering the task to be woken when one is queued.  `None` once the client has stopped
+    /// and all packets are received.
+    #[cfg(feature = "async")]
+    pub(crate) fn poll_recv(&self, cx: &mut Context<'_>) -> Poll<Option<UpdatePacket>> {
+        let mut state = self.shared.lock();
+        if let Some(packet) = self.take(&mut state) {
+            Poll::Ready(Some(packet))
+        } else if state.senders == 0 {
+            Poll::Ready(None)
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+
+    /// Stops sending.  Packets already queued can still be received.
+    pub(crate) fn close(&self) {
+        self.shared.lock().open = false;
+        self.shared.not_full.notify_all();
+    }
+
+    /// Iterates over packets, blocking for each one.  Ends when the client stops.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter { rx: self }
+    }
+
+    /// Iterates over the packets that are queued, without blocking.
+    pub fn try_iter(&self) -> TryIter<'_> {
+        TryIter { rx: self }
+    }
+}
+
+impl Drop for Receiver {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.open = false;
+        state.queue.clear();
+        self.shared.not_full.notify_all();
+    }
+}
+
+/// Blocking iterator over the packets of a `Receiver`.
+#[derive(Debug)]
+pub struct Iter<'a> {
+    rx: &'a Receiver,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = UpdatePacket;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rx.recv().ok()
+    }
+}
+
+/// Non-blocking iterator over the queued packets of a `Receiver`.
+#[derive(Debug)]
+pub struct TryIter<'a> {
+    rx: &'a Receiver,
+}
+
+impl Iterator for TryIter<'_> {
+    type Item = UpdatePacket;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rx.try_recv().ok()
+    }
+}
+
+/// Owning blocking iterator over the packets of a `Receiver`.
+#[derive(Debug)]
+pub struct IntoIter {
+    rx: Receiver,
+}
+
+impl Iterator for IntoIter {
+    type Item = UpdatePacket;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rx.recv().ok()
+    }
+}
+
+impl IntoIterator for Receiver {
+    type Item = UpdatePacket;
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { rx: self }
+    }
+}
+
+impl<'a> IntoIterator for &'a Receiver {
+    type Item = UpdatePacket;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::DisconnectReason;
+    use crate::ir_data::IrData;
+    use crate::mapped_file::header::Header;
+
+    fn data(tick: i32) -> UpdatePacket {
+        UpdatePacket::Data(IrData::new(Vec::new(), Header::connected(60), tick))
+    }
+
+    fn ticks(rx: &Receiver) -> Vec<Option<i32>> {
+        rx.try_iter()
+            .map(|packet| match packet {
+                UpdatePacket::Data(data) => Some(data.tick()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn send_all(backpressure: Backpressure) -> (Receiver, Diagnostics) {
+        let diagnostics = Diagnostics::new();
+        let (tx, rx) = channel(2, backpressure, diagnostics.clone());
+        tx.send(data(1)).unwrap();
+        tx.send(UpdatePacket::Disconnected {
+            reason: DisconnectReason::NoSession,
+        })
+        .unwrap();
+        tx.send(data(2)).unwrap();
+        tx.send(data(3)).unwrap();
+        (rx, diagnostics)
+    }
+
+    #[test]
+    fn test_drop_oldest_keeps_control_packets() {
+        let (rx, diagnostics) = send_all(Backpressure::DropOldest);
+        assert_eq!(ticks(&rx), vec![None, Some(3)]);
+        assert_eq!(diagnostics.dropped_packets(), 2);
+    }
+
+    #[test]
+    fn test_drop_newest() {
+        let (rx, diagnostics) = send_all(Backpressure::DropNewest);
+        assert_eq!(ticks(&rx), vec![Some(1), None]);
+        assert_eq!(diagnostics.dropped_packets(), 2);
+    }
+
+    #[test]
+    fn test_latest_only() {
+        let (rx, diagnostics) = send_all(Backpressure::LatestOnly);
+        assert_eq!(ticks(&rx), vec![None, Some(3)]);
+        assert_eq!(diagnostics.dropped_packets(), 2);
+    }
+
+    #[test]
+    fn test_block_waits_for_receiver() {
+        let (tx, rx) = channel(1, Backpressure::Block, Diagnostics::new());
+        tx.send(data(1)).unwrap();
+        let sender = std::thread::spawn(move || tx.send(data(2)).is_ok());
+        assert_eq!(ticks(&rx), vec![Some(1)]);
+        assert!(sender.join().unwrap());
+        assert_eq!(ticks(&rx), vec![Some(2)]);
+        assert!(rx.recv().is_err());
+    }
+
+    #[test]
+    fn test_close_keeps_queued_packets() {
+        let (tx, rx) = channel(2, Backpressure::Block, Diagnostics::new());
+        tx.send(data(1)).unwrap();
+        rx.close();
+        assert!(tx.is_closed());
+        assert!(tx.wait_closed(Duration::from_secs(10)));
+        assert!(tx.send(data(2)).is_err());
+        drop(tx);
+        assert_eq!(ticks(&rx), vec![Some(1)]);
+        assert!(rx.recv().is_err());
+    }
+
+    #[test]
+    fn test_dropped_receiver_unblocks_sender() {
+        let (tx, rx) = channel(1, Backpressure::Block, Diagnostics::new());
+        tx.send(data(1)).unwrap();
+        let sender = std::thread::spawn(move || tx.send(data(2)).is_err());
+        std::thread::sleep(Duration::from_millis(20));
+        drop(rx);
+        assert!(sender.join().unwrap());
+    }
+}
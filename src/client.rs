@@ -1,32 +1,102 @@
+use crate::builder::{CaptureMode, ClientBuilder};
+use crate::channel::{IntoIter, Iter, Receiver, Sender, TryIter};
+use crate::diagnostics::Diagnostics;
+use crate::error::{IrError, Result};
 use crate::ir_data::IrData;
-use crate::ir_data_inner::IrDataInner;
+use crate::mapped_file::header::Header;
+#[cfg(windows)]
 use crate::mapped_file::memfile::FileMap;
 use crate::mapped_file::var_header::VarHeader;
+use crate::source::TelemetrySource;
+use serde::Deserialize;
 use std::collections::HashMap;
-use std::{
-    sync::mpsc::{Receiver, SyncSender},
-    time::Duration,
-};
+use std::marker::PhantomData;
+use std::sync::mpsc::{RecvError, RecvTimeoutError, TryRecvError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 /// An update packet will be sent over the channel.    
+/// `UpdatePacket::Connected` will be sent when a session is found, before anything else of the session.  
 /// `UpdatePacket::Data` will be sent in intervals approximately equal to the request update frequency  
 /// `UpdatePacket::SessionInfo` will be sent if the session data was updated. This is checked each data update interval.  
 /// Session info is in the yaml formatted string found in the data. Since the data in session info changes, it is kept as a string to aid in custom
 /// deserialization.  
-/// `UpdatePacket::NotConnected` will be send when the session is not connected. This can be useful for reseting display data
-/// between sessions.  
+/// `UpdatePacket::SessionChanged` will be sent before the first data of a new sub session or session, like going from practice to qualifying.  
+/// `UpdatePacket::VariableHeadersChanged` will be sent when the sim changed the variables during a session, followed by the
+/// new `UpdatePacket::VariableHeaders`.  
+/// `UpdatePacket::Disconnected` will be send when the session is not connected, with the reason. This can be useful for
+/// reseting display data between sessions.  
+/// `UpdatePacket::SkippedTicks` will be sent with `CaptureMode::EveryTick` before a data packet, with the number of ticks
+/// that were overwritten by the sim before they could be read.  
 #[non_exhaustive]
 pub enum UpdatePacket {
     Data(IrData),
     SessionInfo(String),
     VariableHeaders(HashMap<String, VarHeader>),
-    NotConnected,
+    SkippedTicks(u32),
+    Connected {
+        tick_rate: i32,
+        header_version: i32,
+    },
+    SessionChanged {
+        sub_session_id: i32,
+        session_num: i32,
+    },
+    VariableHeadersChanged,
+    Disconnected {
+        reason: DisconnectReason,
+    },
+}
+
+/// Why a client is not receiving data, sent in `UpdatePacket::Disconnected`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DisconnectReason {
+    /// The source could not be opened, usually because the sim is not running.  Sent once until a source is opened.
+    SimNotRunning,
+    /// The sim is running, but no session is loaded, like between sessions or in the menus.
+    NoSession,
+    /// Reading the source failed.
+    Error(String),
+    /// A replay reached the end of the recording.
+    EndOfRecording,
+}
+
+impl std::fmt::Display for DisconnectReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DisconnectReason::SimNotRunning => write!(f, "Sim is not running"),
+            DisconnectReason::NoSession => write!(f, "No session is loaded"),
+            DisconnectReason::Error(e) => write!(f, "Error reading telemetry source: {}", e),
+            DisconnectReason::EndOfRecording => write!(f, "End of recording"),
+        }
+    }
+}
+
+/// The part of the session info identifying the sub session.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+struct SessionIds {
+    weekend_info: WeekendIds,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct WeekendIds {
+    #[serde(rename = "SubSessionID")]
+    sub_session_id: i32,
 }
 
 /// Used to conect to iRacing and get updates.
+/// The client is generic over the `TelemetrySource` it reads from.  `Client::connect` uses the live memory mapped
+/// file on Windows, `Client::connect_with` accepts any other source.  Use `ClientBuilder` for control over the
+/// channel, backpressure and reconnects.
 /// ```
 /// use ir_telemetry::client::*;
+/// # #[cfg(windows)]
+/// # {
 /// // Update twice / second
 /// let rx = Client::connect(2.);
 ///
@@ -38,7 +108,7 @@ pub enum UpdatePacket {
 ///          println!("Speed: {}", data.get::<f32>(var_headers.get("Speed")).unwrap());
 ///        },
 ///        UpdatePacket::SessionInfo(session) => println!("Session: {}", session),
-///        UpdatePacket::NotConnected => println!("Session is not open"),
+///        UpdatePacket::Disconnected { reason } => println!("Not connected: {}", reason),
 ///        UpdatePacket::VariableHeaders(v_header) => {
 ///            println!("# of variables in this session: {:?}", v_header.len());
 ///            var_headers = v_header;
@@ -46,123 +116,638 @@ pub enum UpdatePacket {
 ///        _ => (),
 ///    }
 ///}
+/// # }
 ///```
-pub struct Client {}
-
-impl Client {
-    /// Connect to iRacing and get updates. Utilizes a mpsc channel to send updates.  Sends an `UpdatePacket` when data interval is met.
-    pub fn connect(update_rate: f32) -> Receiver<UpdatePacket> {
-        let (tx, rx) = std::sync::mpsc::sync_channel(4);
-        let mut initial = true;
-
-        std::thread::spawn(move || loop {
-            if !initial {
-                log::warn!("Waiting for iRacing session to load");
-                std::thread::sleep(Duration::from_secs(10));
-            }
-            initial = false;
-            let mut client_inner = ClientInner::connect(update_rate, tx.clone());
-            client_inner.start();
-        });
+pub struct Client<S> {
+    source: PhantomData<S>,
+}
+
+#[cfg(windows)]
+impl Client<FileMap> {
+    /// Connect to iRacing and get updates. Utilizes a channel to send updates.  Sends an `UpdatePacket` when data interval is met.
+    pub fn connect(update_rate: f32) -> ClientHandle {
+        ClientBuilder::new().update_rate(update_rate).connect()
+    }
+
+    /// A builder for a client of the live memory mapped file.
+    pub fn builder() -> ClientBuilder<FileMap> {
+        ClientBuilder::new()
+    }
+}
+
+impl<S: TelemetrySource + 'static> Client<S> {
+    /// Get updates from any `TelemetrySource`.  `open` is called on the client thread each time a connection is
+    /// (re)established, so the source itself does not need to be `Send`.
+    pub fn connect_with<F>(update_rate: f32, open: F) -> ClientHandle
+    where
+        F: FnMut() -> Result<S> + Send + 'static,
+    {
+        ClientBuilder::with_source(open)
+            .update_rate(update_rate)
+            .connect()
+    }
+}
+
+/// A running client, returned by `Client::connect` and `ClientBuilder::connect`.  Updates are received from the
+/// handle like from a channel, and iterating over the handle ends when the client stops.
+/// Dropping the handle stops the client and releases the source.
+/// ```
+/// use ir_telemetry::synthetic::SyntheticBuilder;
+/// use ir_telemetry::{Client, UpdatePacket};
+///
+/// let telemetry = SyntheticBuilder::new(60).session_clock().build();
+/// let client = Client::connect_with(60., move || Ok(telemetry.source()));
+/// for packet in client.iter() {
+///     if let UpdatePacket::Data(_) = packet {
+///         client.stop();
+///     }
+/// }
+/// assert!(client.join().is_ok());
+/// ```
+#[derive(Debug)]
+pub struct ClientHandle {
+    rx: Receiver,
+    worker: JoinHandle<Result<()>>,
+}
+
+impl ClientHandle {
+    pub(crate) fn new(rx: Receiver, worker: JoinHandle<Result<()>>) -> Self {
+        Self { rx, worker }
+    }
+
+    /// Asks the client to stop.  Packets already queued can still be received, after which receiving fails and
+    /// iterating ends.
+    pub fn stop(&self) {
+        self.rx.close();
+    }
+
+    /// Returns true until the client thread has finished.
+    pub fn is_running(&self) -> bool {
+        !self.worker.is_finished()
+    }
+
+    /// Waits for the client thread to finish, after `stop` was called.  Fails with the error that ended the last
+    /// session, or the last attempt to open the source, if the client had not connected again since.  Fails with
+    /// `IrError::ClientPanicked` if the thread panicked, for example in the function opening the source.
+    pub fn join(self) -> Result<()> {
+        self.worker.join().map_err(|panic| {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|m| m.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            IrError::ClientPanicked(message)
+        })?
+    }
+
+    /// The channel the updates are received from.
+    pub fn receiver(&self) -> &Receiver {
+        &self.rx
+    }
+
+    /// Blocks until a packet is received.  Fails once the client has stopped and all packets are received.
+    pub fn recv(&self) -> std::result::Result<UpdatePacket, RecvError> {
+        self.rx.recv()
+    }
 
-        rx
+    /// Returns a packet if one is queued, without blocking.
+    pub fn try_recv(&self) -> std::result::Result<UpdatePacket, TryRecvError> {
+        self.rx.try_recv()
+    }
+
+    /// Blocks until a packet is received or the timeout expires.
+    pub fn recv_timeout(
+        &self,
+        timeout: Duration,
+    ) -> std::result::Result<UpdatePacket, RecvTimeoutError> {
+        self.rx.recv_timeout(timeout)
+    }
+
+    /// Iterates over packets, blocking for each one.  Ends when the client stops.
+    pub fn iter(&self) -> Iter<'_> {
+        self.rx.iter()
+    }
+
+    /// Iterates over the packets that are queued, without blocking.
+    pub fn try_iter(&self) -> TryIter<'_> {
+        self.rx.try_iter()
+    }
+}
+
+impl IntoIterator for ClientHandle {
+    type Item = UpdatePacket;
+    type IntoIter = IntoIter;
+
+    /// The client keeps running until the iterator is dropped.
+    fn into_iter(self) -> Self::IntoIter {
+        self.rx.into_iter()
     }
 }
-struct ClientInner {
-    connection: FileMap,
+
+impl<'a> IntoIterator for &'a ClientHandle {
+    type Item = UpdatePacket;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.rx.iter()
+    }
+}
+
+/// The number, offset and row length of the variables, which change when the sim loads different variables.
+fn layout(header: &Header) -> (i32, i32, i32) {
+    (header.num_vars, header.var_header_offset, header.buf_len)
+}
+
+pub(crate) struct ClientInner<S> {
+    connection: S,
     tick_count: i32,
     session_info_tick: i32,
     update_interval: std::time::Duration,
     last_update: std::time::Instant,
-    tx: SyncSender<UpdatePacket>,
+    // When the source last signaled new data, cleared once the data it signaled was read.
+    signaled: Option<std::time::Instant>,
+    var_headers: Arc<HashMap<String, VarHeader>>,
+    // The number, offset and row length of the variables when the variable headers were last read.
+    layout: (i32, i32, i32),
+    // The sub session from the session info and the session sent in the last `SessionChanged`.
+    sub_session_id: i32,
+    session: Option<(i32, i32)>,
+    capture: CaptureMode,
+    diagnostics: Diagnostics,
+    tx: Sender,
 }
 
-impl ClientInner {
-    fn new(update_rate: f32, connection: FileMap, tx: SyncSender<UpdatePacket>) -> Self {
+impl<S: TelemetrySource> ClientInner<S> {
+    pub(crate) fn new(update_rate: f32, connection: S, tx: Sender) -> Self {
         let update_per_second = std::time::Duration::from_secs(100) / (update_rate * 100.) as u32;
         Self {
             connection,
             update_interval: update_per_second,
             last_update: std::time::Instant::now(),
+            signaled: None,
             tick_count: i32::MIN,
             session_info_tick: i32::MIN,
+            var_headers: Arc::default(),
+            layout: (0, 0, 0),
+            sub_session_id: 0,
+            session: None,
+            capture: CaptureMode::default(),
+            diagnostics: Diagnostics::default(),
             tx,
         }
     }
 
-    fn connect(updates_per_second: f32, tx: SyncSender<UpdatePacket>) -> Self {
-        let delay = std::time::Duration::from_secs(10);
-        if let Ok(connection) = FileMap::new() {
-            log::info!("Connected to iRacing shared memory map");
-            Self::new(updates_per_second, connection, tx)
-        } else {
-            log::debug!("Waiting for iRacing to start");
-            std::thread::sleep(delay);
-            Self::connect(updates_per_second, tx)
+    /// Sets which ticks are read at each update.  Skipped ticks are counted in `diagnostics`.
+    pub(crate) fn capture(mut self, capture: CaptureMode, diagnostics: Diagnostics) -> Self {
+        self.capture = capture;
+        self.diagnostics = diagnostics;
+        self
+    }
+
+    fn send(&self, packet: UpdatePacket) -> Result<()> {
+        self.tx.send(packet).map_err(|_| IrError::ChannelClosed)
+    }
+
+    fn update(&mut self) -> Result<()> {
+        let header = self.connection.header()?;
+        if layout(&header) != self.layout {
+            self.check_variable_headers(&header)?;
+        }
+        self.update_session_info(&header)?;
+        match self.capture {
+            CaptureMode::Latest => match self.connection.get_new_data() {
+                Ok(new_data) => self.update_data_packet(new_data)?,
+                // The next update will read a newer buffer.
+                Err(e @ IrError::TornRead { .. }) => log::warn!("Skipping update: {}", e),
+                Err(e) => return Err(e),
+            },
+            CaptureMode::EveryTick => {
+                self.last_update = std::time::Instant::now();
+                let latest_tick = header.var_buf[header.most_recent_buffer_index()].tick_count;
+                if latest_tick < self.tick_count {
+                    log::debug!("Tick count went back to {}", latest_tick);
+                    self.tick_count = i32::MIN;
+                }
+                for new_data in self.connection.get_buffered_data(self.tick_count)? {
+                    self.update_every_tick(new_data)?;
+                }
+            }
         }
+        Ok(())
     }
 
-    fn update(&mut self) {
-        let new_data = self.connection.get_new_data();
-        self.update_session_info(&new_data);
-        self.update_data_packet(new_data);
+    /// Stamps the session time, and the latency since the sim signaled new data if it did since the last update.
+    fn stamp(&self, data: &mut IrData) {
+        data.stamp_session_time(self.var_headers.get("SessionTime"));
+        data.stamp_var_headers(self.var_headers.clone());
+        if let Some(signaled) = self.signaled {
+            data.stamp_latency(signaled);
+        }
+    }
+
+    /// Sends each tick in order, after reporting the ticks missing since the previous one.
+    fn update_every_tick(&mut self, mut new_data: IrData) -> Result<()> {
+        let tick = new_data.tick();
+        if self.tick_count != i32::MIN && tick > self.tick_count + 1 {
+            let skipped = (tick - self.tick_count - 1) as u32;
+            log::debug!("Skipped {} ticks before tick {}", skipped, tick);
+            self.diagnostics.record_skipped_ticks(skipped as u64);
+            self.send(UpdatePacket::SkippedTicks(skipped))?;
+        }
+        self.tick_count = tick;
+        self.update_session(&new_data)?;
+        self.stamp(&mut new_data);
+        self.send(UpdatePacket::Data(new_data))
     }
 
     /// Update the data packet if the data_tick (about 60 times a second) has changed
-    fn update_data_packet(&mut self, new_data: IrDataInner) {
+    fn update_data_packet(&mut self, mut new_data: IrData) -> Result<()> {
         self.last_update = std::time::Instant::now();
         if new_data.tick() != self.tick_count {
             self.tick_count = new_data.tick();
-            self.tx
-                .send(UpdatePacket::Data(IrData::new(new_data)))
-                .expect("Channel closed");
+            self.update_session(&new_data)?;
+            self.stamp(&mut new_data);
+            self.send(UpdatePacket::Data(new_data))?;
         }
+        Ok(())
     }
 
     /// Update the session info packet if the session_info_tick (around 1 sec, but depends on what is happening in the seesion) has changed
-    fn update_session_info(&mut self, data: &IrDataInner) {
-        let session_tick = data.session_info_tick();
+    fn update_session_info(&mut self, header: &Header) -> Result<()> {
+        let session_tick = header.session_info_update();
         if session_tick != self.session_info_tick {
             log::trace!("Updating session info, tick: {}", session_tick);
-            let sess = self.connection.session_info();
+            let sess = self.connection.session_info()?;
+            if self.session_info_tick != i32::MIN {
+                // The sim rewrites the session info when it loads something new, which can come with new variables.
+                self.check_variable_headers(header)?;
+            }
             self.session_info_tick = session_tick;
-            self.tx
-                .send(UpdatePacket::SessionInfo(sess))
-                .expect("Channel closed");
+            self.sub_session_id = serde_yaml::from_str::<SessionIds>(&sess)
+                .map(|ids| ids.weekend_info.sub_session_id)
+                .unwrap_or_default();
+            self.send(UpdatePacket::SessionInfo(sess))?;
         }
+        Ok(())
     }
 
-    fn update_variable_headers(&mut self) {
-        let headers: Vec<VarHeader> = self.connection.get_var_headers();
+    /// Sends `SessionChanged` before the first data of a new sub session or session number.
+    fn update_session(&mut self, data: &IrData) -> Result<()> {
+        let Some(session_num) = data.get::<i32>(self.var_headers.get("SessionNum")) else {
+            return Ok(());
+        };
+        let session = (self.sub_session_id, session_num);
+        if self.session != Some(session) {
+            log::debug!("Session changed to {:?}", session);
+            self.session = Some(session);
+            self.send(UpdatePacket::SessionChanged {
+                sub_session_id: session.0,
+                session_num: session.1,
+            })?;
+        }
+        Ok(())
+    }
+
+    fn read_variable_headers(&self) -> Result<HashMap<String, VarHeader>> {
+        let headers: Vec<VarHeader> = self.connection.get_var_headers()?;
         let mut map = HashMap::new();
         for header in headers {
             map.insert(header.name.clone(), header);
         }
-        self.tx
-            .send(UpdatePacket::VariableHeaders(map))
-            .expect("Channel closed")
+        Ok(map)
+    }
+
+    fn update_variable_headers(&mut self, header: &Header) -> Result<()> {
+        self.var_headers = Arc::new(self.read_variable_headers()?);
+        self.layout = layout(header);
+        self.send(UpdatePacket::VariableHeaders((*self.var_headers).clone()))
+    }
+
+    /// Sends the variable headers again if they differ from the ones sent.  They are read again when the layout of the
+    /// variables in the header changes or the session info is updated, so new headers with the same number of
+    /// variables and row length are only noticed with the next session info.
+    fn check_variable_headers(&mut self, header: &Header) -> Result<()> {
+        self.layout = layout(header);
+        let var_headers = self.read_variable_headers()?;
+        if var_headers != *self.var_headers {
+            log::info!("Variable headers changed");
+            self.send(UpdatePacket::VariableHeadersChanged)?;
+            self.var_headers = Arc::new(var_headers);
+            self.send(UpdatePacket::VariableHeaders((*self.var_headers).clone()))?;
+        }
+        Ok(())
+    }
+
+    fn disconnected(&self, reason: DisconnectReason) -> Result<()> {
+        self.send(UpdatePacket::Disconnected { reason })
+    }
+
+    fn connected(&mut self) -> Result<()> {
+        let header = self.connection.header()?;
+        self.send(UpdatePacket::Connected {
+            tick_rate: header.tick_rate(),
+            header_version: header.version(),
+        })?;
+        self.update_variable_headers(&header)
     }
 
-    fn start(&mut self) {
+    /// Sends updates until the session ends.  Fails with `IrError::ChannelClosed` once the client is stopped, or with
+    /// the error reading the source, after sending `Disconnected` for it.
+    pub(crate) fn start(&mut self) -> Result<()> {
         let mut new_session = true;
         loop {
-            if !self.connection.is_connected() {
-                self.tx
-                    .send(UpdatePacket::NotConnected)
-                    .expect("Channel closed");
-                return;
+            if self.tx.is_closed() {
+                return Err(IrError::ChannelClosed);
+            } else if !self.connection.is_connected() {
+                return self.disconnected(DisconnectReason::NoSession);
             } else if self.last_update.elapsed() > self.update_interval {
-                if new_session {
-                    self.update_variable_headers();
+                let updated = if new_session {
                     new_session = false;
+                    self.connected().and_then(|_| self.update())
+                } else {
+                    self.update()
+                };
+                match updated {
+                    Err(IrError::ChannelClosed) => return Err(IrError::ChannelClosed),
+                    Err(e) => {
+                        log::error!("Error reading telemetry source: {}", e);
+                        self.disconnected(DisconnectReason::Error(e.to_string()))?;
+                        return Err(e);
+                    }
+                    Ok(()) => self.signaled = None,
                 }
-                self.update();
+            } else if self.connection.wait_for_update(
+                self.update_interval
+                    .saturating_sub(self.last_update.elapsed()),
+            ) {
+                self.signaled = Some(std::time::Instant::now());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::mapped_file::dumpfile::DumpFile;
+    use crate::synthetic::{SyntheticBuilder, SyntheticSource};
+
+    /// Flags when the source is dropped.
+    struct TrackedSource {
+        source: SyntheticSource,
+        dropped: Arc<AtomicBool>,
+    }
+
+    impl Drop for TrackedSource {
+        fn drop(&mut self) {
+            self.dropped.store(true, Ordering::SeqCst);
+        }
+    }
+
+    impl TelemetrySource for TrackedSource {
+        fn header(&self) -> Result<Header> {
+            self.source.header()
+        }
+
+        fn wait_for_update(&self, timeout: Duration) -> bool {
+            self.source.wait_for_update(timeout)
+        }
+
+        fn get_new_data(&self) -> Result<IrData> {
+            self.source.get_new_data()
+        }
+
+        fn get_var_headers(&self) -> Result<Vec<VarHeader>> {
+            self.source.get_var_headers()
+        }
+
+        fn session_info(&self) -> Result<String> {
+            self.source.session_info()
+        }
+    }
+
+    fn connect(dropped: Arc<AtomicBool>) -> ClientHandle {
+        let telemetry = SyntheticBuilder::new(60).session_clock().build();
+        Client::connect_with(60., move || {
+            Ok(TrackedSource {
+                source: telemetry.source(),
+                dropped: dropped.clone(),
+            })
+        })
+    }
+
+    #[test]
+    fn test_stop_ends_client() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let client = connect(dropped.clone());
+        assert!(client.recv().is_ok());
+        assert!(client.is_running());
+
+        client.stop();
+        // Drains the queued packets, then ends.
+        assert!(client.iter().count() <= 4);
+        assert!(client.join().is_ok());
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_dropping_receiver_releases_source() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let client = connect(dropped.clone());
+        assert!(client.recv().is_ok());
+        drop(client);
+
+        let start = std::time::Instant::now();
+        while !dropped.load(Ordering::SeqCst) {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "Client kept running"
+            );
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    fn data_packets(client: &ClientHandle, count: usize) -> Vec<IrData> {
+        client
+            .iter()
+            .filter_map(|packet| match packet {
+                UpdatePacket::Data(data) => Some(data),
+                _ => None,
+            })
+            .take(count)
+            .collect()
+    }
+
+    #[test]
+    fn test_data_is_stamped() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let client = connect(dropped);
+        let data = data_packets(&client, 20);
+        assert_eq!(data[0].tick_rate(), 60);
+        assert_eq!(data[0].session_time(), Some(data[0].tick() as f64 / 60.));
+        assert!(data[0].captured_at() <= std::time::SystemTime::now());
+        assert!(data.iter().any(|data| data.latency().is_some()));
+    }
+
+    /// Never signals new data, like a source without a data-valid event.
+    struct SilentSource(SyntheticSource);
+
+    impl TelemetrySource for SilentSource {
+        fn header(&self) -> Result<Header> {
+            self.0.header()
+        }
+
+        fn wait_for_update(&self, timeout: Duration) -> bool {
+            self.0.wait_for_update(timeout);
+            false
+        }
+
+        fn get_new_data(&self) -> Result<IrData> {
+            self.0.get_new_data()
+        }
+
+        fn get_var_headers(&self) -> Result<Vec<VarHeader>> {
+            self.0.get_var_headers()
+        }
+
+        fn session_info(&self) -> Result<String> {
+            self.0.session_info()
+        }
+    }
+
+    #[test]
+    fn test_latency_is_unknown_without_signal() {
+        let telemetry = SyntheticBuilder::new(60).session_clock().build();
+        let client = Client::connect_with(60., move || Ok(SilentSource(telemetry.source())));
+        let data = data_packets(&client, 5);
+        assert!(data.iter().all(|data| data.latency().is_none()));
+    }
+
+    /// Switches to other telemetry, without updating the session info.
+    struct SwitchingSource {
+        first: SyntheticSource,
+        second: SyntheticSource,
+        switched: Arc<AtomicBool>,
+    }
+
+    impl SwitchingSource {
+        fn current(&self) -> &SyntheticSource {
+            if self.switched.load(Ordering::SeqCst) {
+                &self.second
             } else {
-                self.connection.wait_for_update(
-                    self.update_interval
-                        .saturating_sub(self.last_update.elapsed()),
-                );
+                &self.first
             }
         }
     }
+
+    impl TelemetrySource for SwitchingSource {
+        fn header(&self) -> Result<Header> {
+            self.current().header()
+        }
+
+        fn wait_for_update(&self, timeout: Duration) -> bool {
+            self.current().wait_for_update(timeout)
+        }
+
+        fn get_new_data(&self) -> Result<IrData> {
+            self.current().get_new_data()
+        }
+
+        fn get_var_headers(&self) -> Result<Vec<VarHeader>> {
+            self.current().get_var_headers()
+        }
+
+        fn session_info(&self) -> Result<String> {
+            self.current().session_info()
+        }
+    }
+
+    #[test]
+    fn test_session_changes_are_sent() {
+        let telemetry = SyntheticBuilder::new(60)
+            .session_clock()
+            .var("SessionNum", "", "Session number", |tick| {
+                (tick >= 3) as i32
+            })
+            .build();
+        let client = Client::connect_with(60., move || Ok(telemetry.source()));
+        let sessions = client
+            .iter()
+            .filter_map(|packet| match packet {
+                UpdatePacket::SessionChanged { session_num, .. } => Some(session_num),
+                _ => None,
+            })
+            .take(2)
+            .collect::<Vec<_>>();
+        assert_eq!(sessions, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_variable_header_changes_are_sent_without_session_info() {
+        let first = SyntheticBuilder::new(60).session_clock().build();
+        let second = SyntheticBuilder::new(60)
+            .session_clock()
+            .var("Gear", "", "Current gear", |_| 3)
+            .build();
+        let switched = Arc::new(AtomicBool::new(false));
+        let source_switched = switched.clone();
+        let client = Client::connect_with(60., move || {
+            Ok(SwitchingSource {
+                first: first.source(),
+                second: second.source(),
+                switched: source_switched.clone(),
+            })
+        });
+        while !matches!(client.recv(), Ok(UpdatePacket::Data(_))) {}
+        switched.store(true, Ordering::SeqCst);
+
+        let mut packets = client
+            .iter()
+            .skip_while(|packet| matches!(packet, UpdatePacket::Data(_)));
+        assert!(matches!(
+            packets.next(),
+            Some(UpdatePacket::VariableHeadersChanged)
+        ));
+        assert!(matches!(
+            packets.next(),
+            Some(UpdatePacket::VariableHeaders(headers)) if headers.contains_key("Gear")
+        ));
+    }
+
+    #[test]
+    fn test_source_errors_are_returned_by_join() {
+        // The dump is truncated, so reading its buffer fails.
+        let client = Client::connect_with(60., || DumpFile::open("tests/data/2024-08-03.dat"));
+        assert!(client.iter().any(|packet| matches!(
+            packet,
+            UpdatePacket::Disconnected {
+                reason: DisconnectReason::Error(_)
+            }
+        )));
+        client.stop();
+        assert!(matches!(client.join(), Err(IrError::OutOfBounds { .. })));
+
+        let client = Client::connect_with(60., || DumpFile::open("missing.dat"));
+        assert!(matches!(
+            client.recv(),
+            Ok(UpdatePacket::Disconnected {
+                reason: DisconnectReason::SimNotRunning
+            })
+        ));
+        client.stop();
+        assert!(matches!(client.join(), Err(IrError::Io(_))));
+    }
+
+    #[test]
+    fn test_panic_is_reported_by_join() {
+        let client = Client::<SyntheticSource>::connect_with(60., || panic!("no source"));
+        assert!(client.recv().is_err());
+        assert!(matches!(
+            client.join(),
+            Err(IrError::ClientPanicked(message)) if message == "no source"
+        ));
+    }
 }
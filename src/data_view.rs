@@ -0,0 +1,86 @@
+use crate::error::Result;
+use crate::handle::VarHandle;
+use crate::ir_data::IrData;
+use crate::mapped_file::header::Header;
+use crate::mapped_file::var_header::VarHeader;
+use crate::types::IrValue;
+use crate::vars::VarValue;
+
+/// A borrowed view of the most recent telemetry buffer, handed to the closure of `TelemetrySource::with_latest`.
+/// Nothing is copied, so reading a few variables with a `VarHandle` avoids copying the whole row each tick.
+/// The sim may overwrite the buffer while the view is read, in which case the values are thrown away and the closure
+/// is called again on the newer buffer.
+/// ```
+/// use ir_telemetry::synthetic::SyntheticBuilder;
+/// use ir_telemetry::{vars, TelemetrySource};
+///
+/// let telemetry = SyntheticBuilder::new(60)
+///     .var("Speed", "m/s", "GPS vehicle speed", |_| 42.5f32)
+///     .build();
+/// let source = telemetry.source();
+/// let speed = vars::SPEED.handle(&telemetry.var_headers()).unwrap();
+/// let (tick, speed) = source
+///     .with_latest(|view| (view.tick(), view.read(&speed)))
+///     .unwrap();
+/// assert_eq!(speed.unwrap(), 42.5);
+/// # let _ = tick;
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DataView<'a> {
+    row: &'a [u8],
+    header: &'a Header,
+    tick: i32,
+}
+
+impl<'a> DataView<'a> {
+    /// A view of a buffer row read with `header`, holding the data of `tick`.
+    pub fn new(row: &'a [u8], header: &'a Header, tick: i32) -> Self {
+        Self { row, header, tick }
+    }
+
+    /// Returns the tick count of the buffer when the view was taken.
+    pub fn tick(&self) -> i32 {
+        self.tick
+    }
+
+    /// Returns the header the view was taken with.
+    pub fn header(&self) -> &Header {
+        self.header
+    }
+
+    /// Returns the raw row.
+    pub fn data(&self) -> &[u8] {
+        self.row
+    }
+
+    /// Reads a variable, or the first entry of an array.
+    pub fn read<T: VarValue>(&self, handle: &VarHandle<T>) -> Result<T> {
+        handle.read_row_at(self.row, 0)
+    }
+
+    /// Reads the entry at `index` of an array.
+    pub fn read_at<T: VarValue>(&self, handle: &VarHandle<T>, index: usize) -> Result<T> {
+        handle.read_row_at(self.row, index)
+    }
+
+    /// Reads the entries of an array into `values`, up to the length of either.  Returns the number of entries read.
+    pub fn read_into<T: VarValue>(&self, handle: &VarHandle<T>, values: &mut [T]) -> Result<usize> {
+        handle.read_row_into(self.row, values)
+    }
+
+    /// Like `IrData::get`.
+    pub fn get<T: TryFrom<IrValue>>(&self, variable: Option<&VarHeader>) -> Option<T> {
+        let variable = variable?;
+        let end = variable.offset.max(0) as usize + variable.size();
+        if end > self.row.len() {
+            log::error!("Variable {} is out of the buffer", variable.name());
+            return None;
+        }
+        T::try_from(IrValue::from((variable, self.row))).ok()
+    }
+
+    /// Copies the row into owned data.
+    pub fn to_data(&self) -> IrData {
+        IrData::new(self.row.to_vec(), *self.header, self.tick)
+    }
+}
@@ -0,0 +1,77 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+#[derive(Debug, Default)]
+struct Counters {
+    torn_reads: AtomicU64,
+    dropped_reads: AtomicU64,
+    dropped_packets: AtomicU64,
+    skipped_ticks: AtomicU64,
+}
+
+/// Counters describing the health of a client and its telemetry source.  Clones share the same counters, so a copy can be kept
+/// while the source is moved to the client thread.
+/// ```no_run
+/// use ir_telemetry::{Client, Diagnostics};
+/// # #[cfg(windows)]
+/// # {
+/// use ir_telemetry::FileMap;
+///
+/// let diagnostics = Diagnostics::new();
+/// let source_diagnostics = diagnostics.clone();
+/// let rx = Client::connect_with(60., move || FileMap::with_diagnostics(source_diagnostics.clone()));
+/// for _packet in rx {
+///     println!("Torn reads so far: {}", diagnostics.torn_reads());
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    counters: Arc<Counters>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of times a buffer was overwritten by the sim while it was being copied.  The copy is retried each time.
+    pub fn torn_reads(&self) -> u64 {
+        self.counters.torn_reads.load(Ordering::Relaxed)
+    }
+
+    /// Number of updates skipped because every copy of the buffer was torn.
+    pub fn dropped_reads(&self) -> u64 {
+        self.counters.dropped_reads.load(Ordering::Relaxed)
+    }
+
+    /// Number of data packets dropped because the receiver did not keep up.  See `Backpressure`.
+    pub fn dropped_packets(&self) -> u64 {
+        self.counters.dropped_packets.load(Ordering::Relaxed)
+    }
+
+    /// Number of ticks the sim wrote but the client never read, with `CaptureMode::EveryTick`.
+    pub fn skipped_ticks(&self) -> u64 {
+        self.counters.skipped_ticks.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record_torn_read(&self) {
+        self.counters.torn_reads.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_dropped_read(&self) {
+        self.counters.dropped_reads.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_dropped_packets(&self, count: u64) {
+        self.counters
+            .dropped_packets
+            .fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_skipped_ticks(&self, count: u64) {
+        self.counters
+            .skipped_ticks
+            .fetch_add(count, Ordering::Relaxed);
+    }
+}
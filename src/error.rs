@@ -1,34 +1,218 @@
 use std::error;
 use std::fmt;
+
+use crate::mapped_file::values::DataVarType;
+use crate::mapped_file::var_header::VarHeader;
+
 pub type Result<T> = std::result::Result<T, IrError>;
 
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum IrError {
-    MemMappingError(String),
+    /// The memory mapped file or its events could not be opened.  The error of the OS is the source.
+    MemMappingError(Box<dyn error::Error + Send + Sync>),
     TypeError,
     VariableNotFound(String),
+    /// The field `name` matches each of the `variables`, ignoring case and underscores.
+    AmbiguousVariable {
+        name: String,
+        variables: Vec<String>,
+    },
+    /// The data has no variable headers attached to look variables up by name.
+    NoVariableHeaders,
+    /// The variable `name` holds `count` values of `var_type`, which can not be read as the `requested` Rust type.
+    MismatchedType {
+        name: String,
+        var_type: DataVarType,
+        count: i32,
+        requested: &'static str,
+    },
+    /// The value of the variable `name` does not fit in the `requested` Rust type.
+    ValueOutOfRange {
+        name: String,
+        requested: &'static str,
+    },
     ExceedsVariableRange,
+    Io(std::io::Error),
+    OutOfBounds {
+        offset: usize,
+        len: usize,
+        size: usize,
+    },
+    UnsupportedVersion(i32),
+    InvalidHeader(String),
+    UnknownVarType(i32),
+    TornRead {
+        attempts: usize,
+    },
+    ChannelClosed,
+    ClientPanicked(String),
 }
 
 impl fmt::Display for IrError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
-            IrError::MemMappingError(ref message) => {
-                write!(f, "Failure to access memory-mapped file: {}", message)
+            IrError::MemMappingError(ref error) => {
+                write!(f, "Failure to access memory-mapped file: {}", error)
             }
             IrError::TypeError => write!(f, "Could not convert value to requested type"),
             IrError::VariableNotFound(ref var_name) => {
                 write!(f, "Variable {} not found in data", var_name)
             }
+            IrError::AmbiguousVariable {
+                ref name,
+                ref variables,
+            } => write!(
+                f,
+                "Field {} matches each of the variables {}",
+                name,
+                variables.join(", ")
+            ),
+            IrError::NoVariableHeaders => write!(f, "No variable headers attached to the data"),
+            IrError::MismatchedType {
+                ref name,
+                var_type,
+                count,
+                requested,
+            } => {
+                if count > 1 {
+                    write!(
+                        f,
+                        "Variable {} is [{}; {}], which can not be read as {}",
+                        name, var_type, count, requested
+                    )
+                } else {
+                    write!(
+                        f,
+                        "Variable {} is {}, which can not be read as {}",
+                        name, var_type, requested
+                    )
+                }
+            }
+            IrError::ValueOutOfRange {
+                ref name,
+                requested,
+            } => write!(
+                f,
+                "The value of variable {} does not fit in {}",
+                name, requested
+            ),
             IrError::ExceedsVariableRange => {
                 write!(f, "Variable exceeds range for type")
             }
+            IrError::Io(ref error) => write!(f, "IO error: {}", error),
+            IrError::OutOfBounds { offset, len, size } => write!(
+                f,
+                "Data at offset {} with length {} exceeds the size of the data ({} bytes)",
+                offset, len, size
+            ),
+            IrError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported header version {}", version)
+            }
+            IrError::InvalidHeader(ref message) => write!(f, "Invalid header: {}", message),
+            IrError::UnknownVarType(type_code) => {
+                write!(f, "Unknown variable type {}", type_code)
+            }
+            IrError::TornRead { attempts } => write!(
+                f,
+                "Buffer was overwritten during each of {} copies",
+                attempts
+            ),
+            IrError::ChannelClosed => {
+                write!(f, "The receiver was dropped or the client was stopped")
+            }
+            IrError::ClientPanicked(ref message) => {
+                write!(f, "The client thread panicked: {}", message)
+            }
         }
     }
 }
 
 impl error::Error for IrError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
-        None
+        match *self {
+            IrError::Io(ref error) => Some(error),
+            IrError::MemMappingError(ref error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for IrError {
+    fn from(error: std::io::Error) -> Self {
+        IrError::Io(error)
+    }
+}
+
+impl IrError {
+    /// The error for the variable of `header` read as the `requested` type.
+    pub(crate) fn mismatched(header: &VarHeader, requested: &'static str) -> Self {
+        IrError::MismatchedType {
+            name: header.name().to_string(),
+            var_type: header._type,
+            count: header.count,
+            requested,
+        }
+    }
+
+    /// Adds the variable and the requested type to an error converting its value.
+    pub(crate) fn for_variable(self, header: &VarHeader, requested: &'static str) -> Self {
+        match self {
+            IrError::TypeError => Self::mismatched(header, requested),
+            IrError::ExceedsVariableRange => IrError::ValueOutOfRange {
+                name: header.name().to_string(),
+                requested,
+            },
+            error => error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use super::*;
+    use crate::synthetic::SyntheticBuilder;
+
+    #[test]
+    fn test_try_get_reports_the_variable() {
+        let telemetry = SyntheticBuilder::new(60)
+            .var("Speed", "m/s", "GPS vehicle speed", |_| 1e20f32)
+            .array("CarIdxLap", 4, "", "Laps started by car index", |_, _| 1)
+            .build();
+        let data = telemetry.data_at(0);
+        assert!(matches!(
+            data.try_get::<i32>("Speed"),
+            Err(IrError::ValueOutOfRange { ref name, requested: "i32" }) if name == "Speed"
+        ));
+        let error = data.try_get::<f32>("CarIdxLap").unwrap_err();
+        assert!(matches!(
+            error,
+            IrError::MismatchedType {
+                var_type: DataVarType::Int,
+                count: 4,
+                ..
+            }
+        ));
+        assert_eq!(
+            error.to_string(),
+            "Variable CarIdxLap is [Int; 4], which can not be read as f32"
+        );
+        assert!(matches!(
+            crate::IrData::new(Vec::new(), Default::default(), 0).try_get::<f32>("Speed"),
+            Err(IrError::NoVariableHeaders)
+        ));
+    }
+
+    #[test]
+    fn test_mapping_errors_have_a_source() {
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "no mapping");
+        let error = IrError::MemMappingError(io.into());
+        assert_eq!(error.source().unwrap().to_string(), "no mapping");
+        assert_eq!(
+            error.to_string(),
+            "Failure to access memory-mapped file: no mapping"
+        );
     }
 }
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use crate::error::{IrError, Result};
+use crate::ir_data::IrData;
+use crate::mapped_file::values::DataVarType;
+use crate::mapped_file::var_header::VarHeader;
+use crate::vars::VarValue;
+
+/// A variable resolved once from its `VarHeader`, for reads straight from the buffer row without building `IrValue`s.
+/// Scalars are read with `read`, arrays into a slice with `read_into` or a fixed array with `read_array`, so reading
+/// the `CarIdx*` variables every update does not allocate.
+/// ```
+/// use ir_telemetry::synthetic::SyntheticBuilder;
+/// use ir_telemetry::vars;
+///
+/// let telemetry = SyntheticBuilder::new(60)
+///     .array("CarIdxLapDistPct", 64, "%", "Lap distance", |_, i| i as f32 / 64.)
+///     .build();
+/// let lap_dist = vars::CAR_IDX_LAP_DIST_PCT
+///     .handle(&telemetry.var_headers())
+///     .unwrap();
+///
+/// let mut laps = [0f32; 64];
+/// for tick in 0..3 {
+///     lap_dist.read_into(&telemetry.data_at(tick), &mut laps).unwrap();
+/// }
+/// assert_eq!(laps[32], 0.5);
+/// ```
+pub struct VarHandle<T> {
+    var_type: DataVarType,
+    offset: usize,
+    count: usize,
+    value: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for VarHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for VarHandle<T> {}
+
+impl<T> std::fmt::Debug for VarHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VarHandle")
+            .field("var_type", &self.var_type)
+            .field("offset", &self.offset)
+            .field("count", &self.count)
+            .field("type", &std::any::type_name::<T>())
+            .finish()
+    }
+}
+
+impl<T: VarValue> VarHandle<T> {
+    /// Checks that the variable can be read as `T`.  The handle is only valid for data of the session the header is
+    /// from.
+    pub fn new(header: &VarHeader) -> Result<Self> {
+        if !T::accepts(header._type) {
+            return Err(IrError::mismatched(header, std::any::type_name::<T>()));
+        }
+        Ok(Self {
+            var_type: header._type,
+            offset: header.offset.max(0) as usize,
+            count: header.count.max(0) as usize,
+            value: PhantomData,
+        })
+    }
+
+    /// Finds the variable named `name` in the variable headers of a session.
+    pub fn resolve(var_headers: &HashMap<String, VarHeader>, name: &str) -> Result<Self> {
+        let header = var_headers
+            .get(name)
+            .ok_or_else(|| IrError::VariableNotFound(name.to_string()))?;
+        Self::new(header)
+    }
+
+    /// Number of entries.  Greater than one for arrays.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Reads the value, or the first entry of an array.
+    pub fn read(&self, data: &IrData) -> Result<T> {
+        self.read_row_at(data.data(), 0)
+    }
+
+    /// Reads the entry at `index` of an array.
+    pub fn read_at(&self, data: &IrData, index: usize) -> Result<T> {
+        self.read_row_at(data.data(), index)
+    }
+
+    /// Reads the entries of an array into `values`, up to the length of either.  Returns the number of entries read.
+    pub fn read_into(&self, data: &IrData, values: &mut [T]) -> Result<usize> {
+        self.read_row_into(data.data(), values)
+    }
+
+    /// Reads the first `N` entries of an array.  Entries past the end of the variable are left to their default.
+    pub fn read_array<const N: usize>(&self, data: &IrData) -> Result<[T; N]>
+    where
+        T: Copy + Default,
+    {
+        let mut values = [T::default(); N];
+        self.read_into(data, &mut values)?;
+        Ok(values)
+    }
+
+    /// Reads every entry of an array with a single allocation.
+    pub fn to_vec(&self, data: &IrData) -> Result<Vec<T>> {
+        self.entries(data.data())?
+            .map(|bytes| T::decode(self.var_type, bytes))
+            .collect()
+    }
+
+    /// The bytes of each entry in a buffer row.
+    fn entries<'a>(&self, row: &'a [u8]) -> Result<std::slice::ChunksExact<'a, u8>> {
+        let size = self.var_type.amount_of_bytes();
+        let len = size * self.count;
+        let bytes = row
+            .get(self.offset..self.offset + len)
+            .ok_or(IrError::OutOfBounds {
+                offset: self.offset,
+                len,
+                size: row.len(),
+            })?;
+        Ok(bytes.chunks_exact(size))
+    }
+
+    pub(crate) fn read_row_at(&self, row: &[u8], index: usize) -> Result<T> {
+        if index >= self.count {
+            return Err(IrError::ExceedsVariableRange);
+        }
+        let size = self.var_type.amount_of_bytes();
+        let offset = self.offset + index * size;
+        let bytes = row.get(offset..offset + size).ok_or(IrError::OutOfBounds {
+            offset,
+            len: size,
+            size: row.len(),
+        })?;
+        T::decode(self.var_type, bytes)
+    }
+
+    pub(crate) fn read_row_into(&self, row: &[u8], values: &mut [T]) -> Result<usize> {
+        let mut read = 0;
+        for (value, bytes) in values.iter_mut().zip(self.entries(row)?) {
+            *value = T::decode(self.var_type, bytes)?;
+            read += 1;
+        }
+        Ok(read)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synthetic::SyntheticBuilder;
+    use crate::types::TrackLocation;
+    use crate::vars;
+
+    #[test]
+    fn test_handles_read_like_get() {
+        let telemetry = SyntheticBuilder::new(60)
+            .var("Speed", "m/s", "GPS vehicle speed", |tick| {
+                tick as f32 * 1.5
+            })
+            .var("SessionTime", "s", "Seconds since session start", |tick| {
+                tick as f64 / 60.
+            })
+            .array(
+                "CarIdxTrackSurface",
+                4,
+                "irsdk_TrkLoc",
+                "Track surface",
+                |_, i| i as i32 - 1,
+            )
+            .build();
+        let var_headers = telemetry.var_headers();
+        let speed = vars::SPEED.handle(&var_headers).unwrap();
+        let session_time = vars::SESSION_TIME.handle(&var_headers).unwrap();
+        let surfaces = vars::CAR_IDX_TRACK_SURFACE.handle(&var_headers).unwrap();
+
+        for tick in 0..5 {
+            let data = telemetry.data_at(tick);
+            assert_eq!(
+                speed.read(&data).unwrap(),
+                data.get::<f32>(var_headers.get("Speed")).unwrap()
+            );
+            assert_eq!(
+                session_time.read(&data).unwrap(),
+                data.get::<f64>(var_headers.get("SessionTime")).unwrap()
+            );
+            assert_eq!(
+                surfaces.to_vec(&data).unwrap(),
+                data.get::<Vec<TrackLocation>>(var_headers.get("CarIdxTrackSurface"))
+                    .unwrap()
+            );
+        }
+
+        let data = telemetry.data_at(0);
+        let mut values = [TrackLocation::NotInWorld; 2];
+        assert_eq!(surfaces.read_into(&data, &mut values).unwrap(), 2);
+        assert_eq!(values[1], TrackLocation::OffTrack);
+        assert_eq!(
+            surfaces.read_at(&data, 3).unwrap(),
+            TrackLocation::AproachingPits
+        );
+        assert!(matches!(
+            surfaces.read_at(&data, 4),
+            Err(IrError::ExceedsVariableRange)
+        ));
+        let raw = VarHandle::<i32>::resolve(&var_headers, "CarIdxTrackSurface").unwrap();
+        assert_eq!(raw.read_array::<6>(&data).unwrap(), [-1, 0, 1, 2, 0, 0]);
+
+        assert!(matches!(
+            VarHandle::<bool>::resolve(&var_headers, "Speed"),
+            Err(IrError::MismatchedType { .. })
+        ));
+        assert!(matches!(
+            VarHandle::<f32>::resolve(&var_headers, "RPM"),
+            Err(IrError::VariableNotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,388 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use bitflags::bitflags;
+
+use crate::builder::ClientBuilder;
+use crate::channel::{channel, Backpressure, Receiver, Sender};
+use crate::client::{ClientHandle, UpdatePacket};
+use crate::diagnostics::Diagnostics;
+use crate::mapped_file::var_header::VarHeader;
+use crate::source::TelemetrySource;
+
+bitflags! {
+    /// The kinds of `UpdatePacket` a subscriber receives.  `VARIABLE_HEADERS` includes `VariableHeadersChanged`.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+    pub struct PacketFilter: u32 {
+        const DATA = 0x01;
+        const SESSION_INFO = 0x02;
+        const VARIABLE_HEADERS = 0x04;
+        const DISCONNECTED = 0x08;
+        const SKIPPED_TICKS = 0x10;
+        const CONNECTED = 0x20;
+        const SESSION_CHANGED = 0x40;
+    }
+}
+
+impl PacketFilter {
+    fn matches(&self, packet: &UpdatePacket) -> bool {
+        let kind = match packet {
+            UpdatePacket::Data(_) => PacketFilter::DATA,
+            UpdatePacket::SessionInfo(_) => PacketFilter::SESSION_INFO,
+            UpdatePacket::VariableHeaders(_) => PacketFilter::VARIABLE_HEADERS,
+            UpdatePacket::VariableHeadersChanged => PacketFilter::VARIABLE_HEADERS,
+            UpdatePacket::Disconnected { .. } => PacketFilter::DISCONNECTED,
+            UpdatePacket::SkippedTicks(_) => PacketFilter::SKIPPED_TICKS,
+            UpdatePacket::Connected { .. } => PacketFilter::CONNECTED,
+            UpdatePacket::SessionChanged { .. } => PacketFilter::SESSION_CHANGED,
+        };
+        self.contains(kind)
+    }
+}
+
+impl Default for PacketFilter {
+    /// All packets.
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+/// The options of a subscriber to a `Hub`.
+#[derive(Debug, Clone)]
+pub struct Subscription {
+    update_rate: Option<f32>,
+    filter: PacketFilter,
+    capacity: usize,
+    backpressure: Backpressure,
+    diagnostics: Diagnostics,
+}
+
+impl Default for Subscription {
+    fn default() -> Self {
+        Self {
+            update_rate: None,
+            filter: PacketFilter::default(),
+            capacity: 4,
+            backpressure: Backpressure::DropOldest,
+            diagnostics: Diagnostics::default(),
+        }
+    }
+}
+
+impl Subscription {
+    /// Every packet of the hub, dropping the oldest data when the subscriber falls behind.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Data updates per second.  Limited by the update rate of the hub, which is the default.
+    pub fn update_rate(mut self, update_rate: f32) -> Self {
+        self.update_rate = Some(update_rate);
+        self
+    }
+
+    /// The kinds of packets to receive.  Defaults to all.
+    pub fn filter(mut self, filter: PacketFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Number of packets queued for the subscriber before `backpressure` applies.  Defaults to 4.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// What to do when the subscriber falls behind.  Defaults to `Backpressure::DropOldest`.
+    /// `Backpressure::Block` holds up the packets of every other subscriber until this one catches up, but not
+    /// `Hub::subscribe`.
+    pub fn backpressure(mut self, backpressure: Backpressure) -> Self {
+        self.backpressure = backpressure;
+        self
+    }
+
+    /// Counts packets dropped by backpressure.
+    pub fn diagnostics(mut self, diagnostics: Diagnostics) -> Self {
+        self.diagnostics = diagnostics;
+        self
+    }
+}
+
+struct Subscriber {
+    tx: Sender,
+    filter: PacketFilter,
+    interval: Option<Duration>,
+    last_data: Option<Instant>,
+}
+
+impl Subscriber {
+    /// Sends the packet if the subscriber wants it.  Returns false once the subscriber is gone.
+    fn offer(&mut self, packet: &UpdatePacket) -> bool {
+        if !self.filter.matches(packet) {
+            return !self.tx.is_closed();
+        }
+        if let (UpdatePacket::Data(_), Some(interval)) = (packet, self.interval) {
+            let now = Instant::now();
+            if self.last_data.is_some_and(|last| now - last < interval) {
+                return !self.tx.is_closed();
+            }
+            self.last_data = Some(now);
+        }
+        self.tx.send(packet.clone()).is_ok()
+    }
+}
+
+/// The subscribers and the state of the connection, sent to late subscribers.
+#[derive(Default)]
+struct HubState {
+    subscribers: Vec<Arc<Mutex<Subscriber>>>,
+    connected: Option<UpdatePacket>,
+    var_headers: Option<HashMap<String, VarHeader>>,
+    session_info: Option<String>,
+    session: Option<UpdatePacket>,
+}
+
+impl HubState {
+    fn update(&mut self, packet: &UpdatePacket) {
+        match packet {
+            UpdatePacket::Connected { .. } => self.connected = Some(packet.clone()),
+            UpdatePacket::VariableHeaders(headers) => self.var_headers = Some(headers.clone()),
+            UpdatePacket::SessionInfo(session) => self.session_info = Some(session.clone()),
+            UpdatePacket::SessionChanged { .. } => self.session = Some(packet.clone()),
+            UpdatePacket::Disconnected { .. } => {
+                self.connected = None;
+                self.var_headers = None;
+                self.session_info = None;
+                self.session = None;
+            }
+            _ => (),
+        }
+    }
+
+    /// The packets describing the current connection, in the order the client sent them.
+    fn current(&self) -> Vec<UpdatePacket> {
+        let var_headers = self.var_headers.clone().map(UpdatePacket::VariableHeaders);
+        let session_info = self.session_info.clone().map(UpdatePacket::SessionInfo);
+        [
+            self.connected.clone(),
+            var_headers,
+            session_info,
+            self.session.clone(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+/// Shares one client between many subscribers, each with its own update rate and packet filter.
+/// The client reads the source once per update and every subscriber receives a clone of the same `IrData`, which
+/// shares the copied buffer.  Subscribers joining late first receive the packets describing the current connection: `Connected`, the variable
+/// headers, the session info and `SessionChanged`.
+/// ```
+/// use ir_telemetry::hub::{Hub, PacketFilter, Subscription};
+/// use ir_telemetry::synthetic::SyntheticBuilder;
+/// use ir_telemetry::ClientBuilder;
+///
+/// let telemetry = SyntheticBuilder::new(60).session_clock().build();
+/// let hub = Hub::connect(ClientBuilder::with_source(move || Ok(telemetry.source())));
+/// let inputs = hub.subscribe(Subscription::new());
+/// let standings = hub.subscribe(
+///     Subscription::new()
+///         .update_rate(1.)
+///         .filter(PacketFilter::DATA | PacketFilter::SESSION_INFO),
+/// );
+/// # drop((inputs, standings));
+/// ```
+pub struct Hub {
+    client: Arc<ClientHandle>,
+    state: Arc<Mutex<HubState>>,
+}
+
+impl std::fmt::Debug for Hub {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Hub")
+            .field("client", &self.client)
+            .field("subscribers", &self.subscriber_count())
+            .finish()
+    }
+}
+
+fn lock<T>(state: &Mutex<T>) -> MutexGuard<'_, T> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl Hub {
+    /// Starts the client configured by `builder`.  The update rate of the builder is the highest rate available
+    /// to subscribers.
+    pub fn connect<S: TelemetrySource + 'static>(builder: ClientBuilder<S>) -> Self {
+        let client = Arc::new(builder.connect());
+        let state = Arc::new(Mutex::new(HubState::default()));
+
+        let dispatch_client = client.clone();
+        let dispatch_state = state.clone();
+        std::thread::spawn(move || {
+            for packet in dispatch_client.iter() {
+                // Offered without holding the lock, so a subscriber blocking on backpressure does not hold up
+                // `subscribe` and `subscriber_count`.  Subscribers added in the meantime got the updated state.
+                let subscribers = {
+                    let mut state = lock(&dispatch_state);
+                    state.update(&packet);
+                    state.subscribers.clone()
+                };
+                let gone = subscribers
+                    .into_iter()
+                    .filter(|s| !lock(s).offer(&packet))
+                    .collect::<Vec<_>>();
+                if !gone.is_empty() {
+                    lock(&dispatch_state)
+                        .subscribers
+                        .retain(|s| !gone.iter().any(|gone| Arc::ptr_eq(s, gone)));
+                }
+            }
+            // Ends the subscriptions.
+            lock(&dispatch_state).subscribers.clear();
+            log::debug!("Hub stopped");
+        });
+
+        Self { client, state }
+    }
+
+    /// Adds a subscriber.  Dropping the receiver ends the subscription.
+    pub fn subscribe(&self, subscription: Subscription) -> Receiver {
+        let (tx, rx) = channel(
+            subscription.capacity,
+            subscription.backpressure,
+            subscription.diagnostics,
+        );
+        let mut subscriber = Subscriber {
+            tx,
+            filter: subscription.filter,
+            interval: subscription
+                .update_rate
+                .map(|rate| Duration::from_secs_f32(1. / rate.max(f32::EPSILON))),
+            last_data: None,
+        };
+
+        let mut state = lock(&self.state);
+        if !self.client.is_running() {
+            // The receiver ends right away.
+            return rx;
+        }
+        for packet in state.current() {
+            subscriber.offer(&packet);
+        }
+        state.subscribers.push(Arc::new(Mutex::new(subscriber)));
+        rx
+    }
+
+    /// Number of active subscribers.
+    pub fn subscriber_count(&self) -> usize {
+        lock(&self.state).subscribers.len()
+    }
+
+    /// Stops the client.  Subscribers receive the packets already queued, after which their receivers end.
+    pub fn stop(&self) {
+        self.client.stop();
+    }
+
+    /// Returns true until the client thread has finished.
+    pub fn is_running(&self) -> bool {
+        self.client.is_running()
+    }
+}
+
+impl Drop for Hub {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::synthetic::SyntheticBuilder;
+
+    fn hub() -> Hub {
+        let telemetry = SyntheticBuilder::new(60).session_clock().build();
+        Hub::connect(ClientBuilder::with_source(move || Ok(telemetry.source())))
+    }
+
+    #[test]
+    fn test_subscribers_get_their_own_rate_and_filter() {
+        let hub = hub();
+        let all = hub.subscribe(Subscription::new().capacity(64));
+        let slow = hub.subscribe(
+            Subscription::new()
+                .update_rate(5.)
+                .filter(PacketFilter::DATA)
+                .capacity(64),
+        );
+        std::thread::sleep(Duration::from_millis(500));
+        hub.stop();
+
+        let all = all.iter().collect::<Vec<_>>();
+        let slow = slow.iter().collect::<Vec<_>>();
+        assert!(matches!(all[0], UpdatePacket::Connected { .. }));
+        assert!(matches!(all[1], UpdatePacket::VariableHeaders(_)));
+        assert!(matches!(all[2], UpdatePacket::SessionInfo(_)));
+        assert!(slow.iter().all(|p| matches!(p, UpdatePacket::Data(_))));
+        assert!((1..=4).contains(&slow.len()), "{} packets", slow.len());
+        assert!(all.len() > slow.len() + 10);
+
+        // Both subscribers share the buffer of the first update.
+        let first_data = |packets: &[UpdatePacket]| {
+            packets.iter().find_map(|p| match p {
+                UpdatePacket::Data(data) => Some(data.clone()),
+                _ => None,
+            })
+        };
+        let (all, slow) = (first_data(&all).unwrap(), first_data(&slow).unwrap());
+        assert_eq!(all.tick(), slow.tick());
+        assert_eq!(all.data().as_ptr(), slow.data().as_ptr());
+    }
+
+    #[test]
+    fn test_blocked_subscriber_does_not_block_the_hub() {
+        let hub = hub();
+        let blocked = hub.subscribe(
+            Subscription::new()
+                .capacity(1)
+                .backpressure(Backpressure::Block),
+        );
+        // The dispatch thread is now waiting for the blocked subscriber to make room.
+        std::thread::sleep(Duration::from_millis(100));
+
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                let late = hub.subscribe(Subscription::new());
+                tx.send(hub.subscriber_count()).unwrap();
+                drop(late);
+            });
+            let count = rx.recv_timeout(Duration::from_secs(1));
+            drop(blocked);
+            assert_eq!(count, Ok(2));
+        });
+    }
+
+    #[test]
+    fn test_late_subscriber_gets_headers_and_session_info() {
+        let hub = hub();
+        let first = hub.subscribe(Subscription::new());
+        // Wait for the first data, after the connection, headers and session info.
+        while !matches!(first.recv(), Ok(UpdatePacket::Data(_))) {}
+
+        let late = hub.subscribe(Subscription::new());
+        assert!(matches!(late.recv(), Ok(UpdatePacket::Connected { .. })));
+        assert!(matches!(late.recv(), Ok(UpdatePacket::VariableHeaders(_))));
+        assert!(matches!(late.recv(), Ok(UpdatePacket::SessionInfo(_))));
+        assert_eq!(hub.subscriber_count(), 2);
+
+        drop(first);
+        while hub.subscriber_count() > 1 {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+}
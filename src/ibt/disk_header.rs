@@ -0,0 +1,74 @@
+use std::time::{Duration, SystemTime};
+
+use crate::error::{IrError, Result};
+
+/// The sub header following the `Header` in .ibt files.  Describes the recorded session.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct DiskSubHeader {
+    pub(crate) session_start_date: i64, // time_t of the start of the recording
+    pub(crate) session_start_time: f64, // SessionTime of the first record
+    pub(crate) session_end_time: f64,   // SessionTime of the last record
+    pub(crate) session_lap_count: i32,
+    pub(crate) session_record_count: i32,
+}
+
+impl DiskSubHeader {
+    /// Reads a sub header from the start of `bytes`.
+    pub(crate) fn parse(bytes: &[u8]) -> Result<Self> {
+        let size = std::mem::size_of::<DiskSubHeader>();
+        if bytes.len() < size {
+            return Err(IrError::OutOfBounds {
+                offset: 0,
+                len: size,
+                size: bytes.len(),
+            });
+        }
+        // The fields are little endian, at the offsets `to_bytes` writes them to.
+        let eight = |start: usize| bytes[start..start + 8].try_into().unwrap();
+        let four = |start: usize| bytes[start..start + 4].try_into().unwrap();
+        Ok(Self {
+            session_start_date: i64::from_le_bytes(eight(0)),
+            session_start_time: f64::from_le_bytes(eight(8)),
+            session_end_time: f64::from_le_bytes(eight(16)),
+            session_lap_count: i32::from_le_bytes(four(24)),
+            session_record_count: i32::from_le_bytes(four(28)),
+        })
+    }
+
+    /// Serializes the sub header in the layout of an .ibt file.
+    pub(crate) fn to_bytes(self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(std::mem::size_of::<DiskSubHeader>());
+        bytes.extend_from_slice(&self.session_start_date.to_le_bytes());
+        bytes.extend_from_slice(&self.session_start_time.to_le_bytes());
+        bytes.extend_from_slice(&self.session_end_time.to_le_bytes());
+        bytes.extend_from_slice(&self.session_lap_count.to_le_bytes());
+        bytes.extend_from_slice(&self.session_record_count.to_le_bytes());
+        bytes
+    }
+
+    /// The wall clock date and time the recording started.
+    pub fn start_date(&self) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(self.session_start_date.max(0) as u64)
+    }
+
+    /// The `SessionTime`, in seconds, of the first record.
+    pub fn start_time(&self) -> f64 {
+        self.session_start_time
+    }
+
+    /// The `SessionTime`, in seconds, of the last record.
+    pub fn end_time(&self) -> f64 {
+        self.session_end_time
+    }
+
+    /// Number of laps recorded.
+    pub fn lap_count(&self) -> i32 {
+        self.session_lap_count
+    }
+
+    /// Number of records (rows of telemetry) in the file.
+    pub fn record_count(&self) -> i32 {
+        self.session_record_count
+    }
+}
@@ -0,0 +1,329 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::{IrError, Result};
+use crate::ibt::disk_header::DiskSubHeader;
+use crate::ir_data::IrData;
+use crate::mapped_file::header::Header;
+use crate::mapped_file::var_header::{parse_var_headers, VarHeader, VarHeaderData};
+use crate::utils::byte_array_to_rust_string;
+
+/// An iRacing disk telemetry (.ibt) file.
+/// The file starts with the same `Header` as the memory mapped file followed by a `DiskSubHeader`, the variable
+/// headers and the session info.  The telemetry follows as back-to-back rows, one per tick.
+/// Only the headers are read when opening, records are read from disk on request.
+/// ```no_run
+/// use ir_telemetry::IbtFile;
+///
+/// let ibt = IbtFile::open("session.ibt").unwrap();
+/// let var_headers = ibt.var_header_map();
+/// for record in ibt.records() {
+///     let record = record.unwrap();
+///     println!("Speed: {:?}", record.get::<f32>(var_headers.get("Speed")));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct IbtFile {
+    file: File,
+    file_len: usize,
+    header: Header,
+    disk_header: DiskSubHeader,
+    var_headers: Vec<VarHeader>,
+    // Attached to each record.
+    var_header_map: Arc<HashMap<String, VarHeader>>,
+    session_info: String,
+    records: usize,
+}
+
+impl IbtFile {
+    /// Opens an .ibt file and reads the headers and session info.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(path)?;
+        let file_len = file.metadata()?.len() as usize;
+        let header_size = std::mem::size_of::<Header>();
+
+        let header = Header::parse(&read_at(&file, file_len, 0, header_size)?)?;
+        let disk_header = DiskSubHeader::parse(&read_at(
+            &file,
+            file_len,
+            header_size,
+            std::mem::size_of::<DiskSubHeader>(),
+        )?)?;
+
+        let var_header_bytes = read_at(
+            &file,
+            file_len,
+            header.var_header_offset as usize,
+            header.num_vars as usize * std::mem::size_of::<VarHeaderData>(),
+        )?;
+        let var_headers = parse_var_headers(&header, &var_header_bytes)?;
+
+        let session_info = byte_array_to_rust_string(&read_at(
+            &file,
+            file_len,
+            header.session_info_offset as usize,
+            header.session_info_len.max(0) as usize,
+        )?);
+
+        let row_len = header.buf_len.max(0) as usize;
+        let available = file_len
+            .saturating_sub(header.var_buf[0].offset as usize)
+            .checked_div(row_len)
+            .unwrap_or(0);
+        // The record count is only written when iRacing closes the file.
+        let records = match disk_header.session_record_count {
+            count if count > 0 => available.min(count as usize),
+            _ => available,
+        };
+
+        let var_header_map = Arc::new(
+            var_headers
+                .iter()
+                .map(|h| (h.name.clone(), h.clone()))
+                .collect(),
+        );
+
+        Ok(Self {
+            file,
+            file_len,
+            header,
+            disk_header,
+            var_headers,
+            var_header_map,
+            session_info,
+            records,
+        })
+    }
+
+    /// The header of the file.  Describes the layout of the file.
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+
+    /// The session details of the recording.
+    pub fn disk_header(&self) -> &DiskSubHeader {
+        &self.disk_header
+    }
+
+    /// The variable headers, in the order they are stored in the file.
+    pub fn var_headers(&self) -> &[VarHeader] {
+        &self.var_headers
+    }
+
+    /// The variable headers keyed by name, as sent in `UpdatePacket::VariableHeaders`.
+    pub fn var_header_map(&self) -> HashMap<String, VarHeader> {
+        (*self.var_header_map).clone()
+    }
+
+    /// The yaml formatted session info string.
+    pub fn session_info(&self) -> &str {
+        &self.session_info
+    }
+
+    /// Number of records in the file.
+    pub fn len(&self) -> usize {
+        self.records
+    }
+
+    /// Returns true if the file has no records.
+    pub fn is_empty(&self) -> bool {
+        self.records == 0
+    }
+
+    /// Reads the record at `index`.  The tick of the returned data is the index of the record.  The variable headers are
+    /// attached, and the session time and capture time are stamped from the `SessionTime` variable, if recorded.
+    pub fn record(&self, index: usize) -> Result<IrData> {
+        let row_len = self.header.buf_len as usize;
+        let rows_offset = self.header.var_buf[0].offset as usize;
+        if index >= self.records {
+            return Err(IrError::OutOfBounds {
+                offset: index.saturating_mul(row_len).saturating_add(rows_offset),
+                len: row_len,
+                size: self.file_len,
+            });
+        }
+        let offset = rows_offset + index * row_len;
+        let data = read_at(&self.file, self.file_len, offset, row_len)?;
+        let mut data = IrData::new(data, self.header, index as i32)
+            .with_var_headers(self.var_header_map.clone());
+        data.stamp_session_time(self.var_header_map.get("SessionTime"));
+        if let Some(session_time) = data.session_time() {
+            let since_start = session_time - self.disk_header.start_time();
+            let since_start = Duration::try_from_secs_f64(since_start).unwrap_or_default();
+            data.stamp_captured_at(self.disk_header.start_date() + since_start);
+        }
+        Ok(data)
+    }
+
+    /// Iterates over all records in the file.
+    pub fn records(&self) -> Records<'_> {
+        Records {
+            file: self,
+            next: 0,
+        }
+    }
+}
+
+/// Iterator over the records of an `IbtFile`.
+#[derive(Debug)]
+pub struct Records<'a> {
+    file: &'a IbtFile,
+    next: usize,
+}
+
+impl Iterator for Records<'_> {
+    type Item = Result<IrData>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.file.len() {
+            return None;
+        }
+        let record = self.file.record(self.next);
+        self.next += 1;
+        Some(record)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.file.len().saturating_sub(self.next);
+        (remaining, Some(remaining))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.next = self.next.saturating_add(n);
+        self.next()
+    }
+}
+
+impl ExactSizeIterator for Records<'_> {}
+
+fn read_at(mut file: &File, file_len: usize, offset: usize, len: usize) -> Result<Vec<u8>> {
+    if offset.checked_add(len).is_none_or(|end| end > file_len) {
+        return Err(IrError::OutOfBounds {
+            offset,
+            len,
+            size: file_len,
+        });
+    }
+    let mut bytes = vec![0u8; len];
+    file.seek(SeekFrom::Start(offset as u64))?;
+    file.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_i32(bytes: &mut Vec<u8>, value: i32) {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn push_str(bytes: &mut Vec<u8>, value: &str, len: usize) {
+        let mut field = vec![0u8; len];
+        field[..value.len()].copy_from_slice(value.as_bytes());
+        bytes.extend_from_slice(&field);
+    }
+
+    /// Builds a file with the `Speed` (float) and `Lap` (int) variables and one row per speed.
+    fn ibt_bytes(speeds: &[f32]) -> Vec<u8> {
+        let session = "---\nWeekendInfo:\n TrackName: test\n...\n";
+        let var_header_offset = 112 + 32;
+        let session_offset = var_header_offset + 2 * 144;
+        let rows_offset = session_offset + session.len() as i32;
+
+        let mut bytes = Vec::new();
+        for value in [
+            2, // ver
+            1, // status
+            60,
+            0,
+            session.len() as i32,
+            session_offset,
+            2,
+            var_header_offset,
+            1,
+            8, // buf_len
+            0,
+            0,
+        ] {
+            push_i32(&mut bytes, value);
+        }
+        for buffer in 0..4 {
+            let offset = if buffer == 0 { rows_offset } else { 0 };
+            for value in [speeds.len() as i32, offset, 0, 0] {
+                push_i32(&mut bytes, value);
+            }
+        }
+
+        bytes.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+        bytes.extend_from_slice(&10f64.to_le_bytes());
+        bytes.extend_from_slice(&20f64.to_le_bytes());
+        push_i32(&mut bytes, 1);
+        push_i32(&mut bytes, speeds.len() as i32);
+
+        for (type_code, offset, name, unit) in [(4, 0, "Speed", "m/s"), (2, 4, "Lap", "")] {
+            for value in [type_code, offset, 1, 0] {
+                push_i32(&mut bytes, value);
+            }
+            push_str(&mut bytes, name, 32);
+            push_str(&mut bytes, "", 64);
+            push_str(&mut bytes, unit, 32);
+        }
+
+        bytes.extend_from_slice(session.as_bytes());
+        for (lap, speed) in speeds.iter().enumerate() {
+            bytes.extend_from_slice(&speed.to_le_bytes());
+            push_i32(&mut bytes, lap as i32);
+        }
+        bytes
+    }
+
+    fn write_temp(name: &str, bytes: &[u8]) -> std::path::PathBuf {
+        let path = crate::utils::temp_path(name);
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_open_reads_headers_and_records() {
+        let path = write_temp("open.ibt", &ibt_bytes(&[1.0, 2.0, 3.0]));
+        let ibt = IbtFile::open(&path).unwrap();
+
+        assert_eq!(ibt.len(), 3);
+        assert_eq!(ibt.disk_header().lap_count(), 1);
+        assert_eq!(ibt.disk_header().start_time(), 10.);
+        assert!(ibt.session_info().contains("TrackName: test"));
+        assert_eq!(ibt.var_headers()[0].name(), "Speed");
+
+        let headers = ibt.var_header_map();
+        let speeds = ibt
+            .records()
+            .map(|r| r.unwrap().get::<f32>(headers.get("Speed")).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(speeds, vec![1.0, 2.0, 3.0]);
+
+        let record = ibt.record(2).unwrap();
+        assert_eq!(record.tick(), 2);
+        assert_eq!(record.get::<i32>(headers.get("Lap")), Some(2));
+        assert!(ibt.record(3).is_err());
+        assert!(matches!(
+            ibt.record(usize::MAX),
+            Err(IrError::OutOfBounds { .. })
+        ));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_truncated_rows_are_not_counted() {
+        let mut bytes = ibt_bytes(&[1.0, 2.0, 3.0]);
+        bytes.truncate(bytes.len() - 4);
+        let path = write_temp("truncated.ibt", &bytes);
+        let ibt = IbtFile::open(&path).unwrap();
+        assert_eq!(ibt.len(), 2);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,322 @@
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::error::{IrError, Result};
+use crate::ibt::disk_header::DiskSubHeader;
+use crate::ir_data::IrData;
+use crate::mapped_file::header::Header;
+use crate::mapped_file::var_header::{VarHeader, VarHeaderData};
+
+/// Where a variable is copied from in the incoming data and to in the written row.
+#[derive(Debug, Clone, Copy)]
+struct Column {
+    source: usize,
+    target: usize,
+    len: usize,
+}
+
+/// Records telemetry to an .ibt file that can be read with `IbtFile` or any other .ibt tool.
+/// Only the variables passed when creating the writer are recorded, so the variable set and the rate of the
+/// recording are up to the caller.  The record count, lap count and session times are patched into the headers
+/// by `finish`, which is also called when the writer is dropped.
+/// `SessionTime` and `Lap` are used for the session times and lap count when they are part of the recording.
+/// The .ibt format has no place for the client stamps: latency is not written, and the capture time of a record is
+/// rebuilt from the start date, in whole seconds, and `SessionTime` when reading.
+/// ```no_run
+/// use ir_telemetry::{Client, UpdatePacket};
+/// use ir_telemetry::ibt::writer::IbtWriter;
+///
+/// # #[cfg(windows)]
+/// # {
+/// let mut var_headers = std::collections::HashMap::new();
+/// let mut writer = None;
+/// for packet in Client::connect(10.) {
+///     match packet {
+///         UpdatePacket::VariableHeaders(headers) => var_headers = headers,
+///         UpdatePacket::SessionInfo(session) if writer.is_none() => {
+///             writer = Some(IbtWriter::create("session.ibt", var_headers.values(), &session).unwrap());
+///         }
+///         UpdatePacket::Data(data) => {
+///             if let Some(writer) = writer.as_mut() {
+///                 writer.write(&data).unwrap();
+///             }
+///         }
+///         _ => (),
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct IbtWriter<W: Write + Seek> {
+    writer: W,
+    header: Header,
+    disk_header: DiskSubHeader,
+    columns: Vec<Column>,
+    row: Vec<u8>,
+    session_time: Option<VarHeader>,
+    lap: Option<VarHeader>,
+    first_lap: Option<i32>,
+    last_lap: Option<i32>,
+    dirty: bool,
+}
+
+impl IbtWriter<BufWriter<File>> {
+    /// Creates the file at `path`, overwriting any existing file.
+    pub fn create<'a, P, I>(path: P, var_headers: I, session_info: &str) -> Result<Self>
+    where
+        P: AsRef<Path>,
+        I: IntoIterator<Item = &'a VarHeader>,
+    {
+        Self::new(
+            BufWriter::new(File::create(path)?),
+            var_headers,
+            session_info,
+        )
+    }
+}
+
+impl<W: Write + Seek> IbtWriter<W> {
+    /// Writes the headers, variable headers and session info to `writer`.  Records are appended with `write`.
+    pub fn new<'a, I>(mut writer: W, var_headers: I, session_info: &str) -> Result<Self>
+    where
+        I: IntoIterator<Item = &'a VarHeader>,
+    {
+        let mut sources = var_headers.into_iter().collect::<Vec<_>>();
+        sources.sort_by_key(|h| h.offset);
+
+        // Pack the chosen variables into a new row layout.
+        let mut columns = Vec::with_capacity(sources.len());
+        let mut var_headers = Vec::with_capacity(sources.len());
+        let mut row_len = 0;
+        for source in &sources {
+            let len = source.size();
+            columns.push(Column {
+                source: source.offset as usize,
+                target: row_len,
+                len,
+            });
+            let mut var_header = (*source).clone();
+            var_header.offset = row_len as i32;
+            var_headers.push(var_header);
+            row_len += len;
+        }
+
+        let var_header_offset =
+            std::mem::size_of::<Header>() + std::mem::size_of::<DiskSubHeader>();
+        let session_info_offset =
+            var_header_offset + var_headers.len() * std::mem::size_of::<VarHeaderData>();
+        // The session string is nul terminated.
+        let session_info_len = session_info.len() + 1;
+
+        let mut header = Header::connected(0);
+        header.session_info_len = session_info_len as i32;
+        header.session_info_offset = session_info_offset as i32;
+        header.num_vars = var_headers.len() as i32;
+        header.var_header_offset = var_header_offset as i32;
+        header.num_buf = 1;
+        header.buf_len = row_len as i32;
+        header.var_buf[0].offset = (session_info_offset + session_info_len) as i32;
+
+        let disk_header = DiskSubHeader {
+            session_start_date: unix_seconds(SystemTime::now()),
+            ..Default::default()
+        };
+
+        writer.write_all(&header.to_bytes())?;
+        writer.write_all(&disk_header.to_bytes())?;
+        for var_header in &var_headers {
+            writer.write_all(&VarHeaderData::from(var_header).to_bytes())?;
+        }
+        writer.write_all(session_info.as_bytes())?;
+        writer.write_all(&[0])?;
+
+        let find = |name: &str| {
+            sources
+                .iter()
+                .find(|h| h.name == name)
+                .map(|h| (*h).clone())
+        };
+        Ok(Self {
+            writer,
+            header,
+            disk_header,
+            columns,
+            row: vec![0; row_len],
+            session_time: find("SessionTime"),
+            lap: find("Lap"),
+            first_lap: None,
+            last_lap: None,
+            dirty: true,
+        })
+    }
+
+    /// Appends a record.  `data` must have the layout of the variable headers the writer was created with.
+    pub fn write(&mut self, data: &IrData) -> Result<()> {
+        let bytes = data.data();
+        for column in &self.columns {
+            let source = bytes.get(column.source..column.source + column.len).ok_or(
+                IrError::OutOfBounds {
+                    offset: column.source,
+                    len: column.len,
+                    size: bytes.len(),
+                },
+            )?;
+            self.row[column.target..column.target + column.len].copy_from_slice(source);
+        }
+        self.writer.write_all(&self.row)?;
+
+        if self.disk_header.session_record_count == 0 {
+            self.header.tick_rate = data.header().tick_rate();
+            // Records are stamped relative to the first one when read back.
+            self.disk_header.session_start_date = unix_seconds(data.captured_at());
+            if let Some(time) = data.get::<f64>(self.session_time.as_ref()) {
+                self.disk_header.session_start_time = time;
+            }
+        }
+        if let Some(time) = data.get::<f64>(self.session_time.as_ref()) {
+            self.disk_header.session_end_time = time;
+        }
+        if let Some(lap) = data.get::<i32>(self.lap.as_ref()) {
+            self.first_lap.get_or_insert(lap);
+            self.last_lap = Some(lap);
+        }
+        self.header.var_buf[0].tick_count = data.tick();
+        self.disk_header.session_record_count += 1;
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Number of records written so far.
+    pub fn record_count(&self) -> i32 {
+        self.disk_header.session_record_count
+    }
+
+    /// Patches the headers with the record count, lap count and session times, and flushes the writer.
+    /// Records can still be written after finishing.
+    pub fn finish(&mut self) -> Result<()> {
+        if let (Some(first), Some(last)) = (self.first_lap, self.last_lap) {
+            self.disk_header.session_lap_count = last - first + 1;
+        }
+        self.writer.seek(SeekFrom::Start(0))?;
+        self.writer.write_all(&self.header.to_bytes())?;
+        self.writer.write_all(&self.disk_header.to_bytes())?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer.flush()?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+impl<W: Write + Seek> Drop for IbtWriter<W> {
+    fn drop(&mut self) {
+        if self.dirty {
+            if let Err(e) = self.finish() {
+                log::error!("Failed to finish .ibt file: {}", e);
+            }
+        }
+    }
+}
+
+fn unix_seconds(time: SystemTime) -> i64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::ibt::file::IbtFile;
+    use crate::mapped_file::values::DataVarType;
+
+    fn row(speed: f32, lap: i32, time: f64, gear: i32) -> Vec<u8> {
+        let mut row = Vec::new();
+        row.extend_from_slice(&speed.to_le_bytes());
+        row.extend_from_slice(&lap.to_le_bytes());
+        row.extend_from_slice(&time.to_le_bytes());
+        row.extend_from_slice(&gear.to_le_bytes());
+        row
+    }
+
+    #[test]
+    fn test_written_file_can_be_read() {
+        let headers = [
+            VarHeader::new(
+                DataVarType::Float,
+                0,
+                1,
+                "Speed",
+                "GPS vehicle speed",
+                "m/s",
+            ),
+            VarHeader::new(DataVarType::Int, 4, 1, "Lap", "Laps started count", ""),
+            VarHeader::new(
+                DataVarType::Double,
+                8,
+                1,
+                "SessionTime",
+                "Seconds since session start",
+                "s",
+            ),
+            VarHeader::new(
+                DataVarType::Int,
+                16,
+                1,
+                "Gear",
+                "-1=reverse  0=neutral  1..n=current gear",
+                "",
+            ),
+        ];
+        let path = crate::utils::temp_path("writer.ibt");
+        let mut writer = IbtWriter::create(
+            &path,
+            headers.iter().filter(|h| h.name() != "Gear"),
+            "---\nWeekendInfo:\n TrackName: test\n...\n",
+        )
+        .unwrap();
+        let mut start = None;
+        for (tick, (speed, lap, time)) in [(10., 1, 5.), (20., 1, 6.), (30., 2, 7.)]
+            .into_iter()
+            .enumerate()
+        {
+            let data = IrData::new(row(speed, lap, time, 3), Header::connected(60), tick as i32);
+            start.get_or_insert(data.captured_at());
+            writer.write(&data).unwrap();
+        }
+        drop(writer);
+
+        let ibt = IbtFile::open(&path).unwrap();
+        assert_eq!(ibt.len(), 3);
+        assert_eq!(ibt.header().tick_rate(), 60);
+        assert_eq!(ibt.disk_header().record_count(), 3);
+        assert_eq!(ibt.disk_header().lap_count(), 2);
+        assert_eq!(ibt.disk_header().start_time(), 5.);
+        assert_eq!(ibt.disk_header().end_time(), 7.);
+        assert!(ibt.session_info().contains("TrackName: test"));
+
+        let headers = ibt.var_header_map();
+        assert!(!headers.contains_key("Gear"));
+        assert_eq!(headers["SessionTime"].offset(), 8);
+        let last = ibt.record(2).unwrap();
+        assert_eq!(last.data().len(), 16);
+        assert_eq!(last.get::<f32>(headers.get("Speed")), Some(30.));
+        assert_eq!(last.session_time(), Some(7.));
+        assert_eq!(last.tick_rate(), 60);
+        // The start date is stored in whole seconds.
+        let first = ibt.record(0).unwrap().captured_at();
+        let start = start.unwrap().duration_since(first).unwrap();
+        assert!(start < Duration::from_secs(1));
+        assert_eq!(
+            last.captured_at().duration_since(first).unwrap(),
+            Duration::from_secs(2)
+        );
+        assert_eq!(last.latency(), None);
+        assert_eq!(last.get::<i32>(headers.get("Lap")), Some(2));
+        assert_eq!(last.get::<f64>(headers.get("SessionTime")), Some(7.));
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -1,17 +1,28 @@
 use crate::error::*;
 use crate::ir_data_inner::IrDataInner;
 use crate::mapped_file::full_value::{SingleVarType, VarType};
+use crate::mapped_file::header::Header;
 use crate::mapped_file::var_header::VarHeader;
+use crate::record::Record;
 use crate::types::IrValue;
+use crate::units::Quantity;
+use crate::vars::Key;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 /// Contains the latest telemetry data and a convienent hashmap of all the variables.
+/// Cloning is cheap, clones share the same copy of the buffer.
 #[derive(Debug, Clone)]
 pub struct IrData {
-    data: IrDataInner,
+    data: Arc<IrDataInner>,
 }
 
 impl IrData {
-    pub(crate) fn new(data: IrDataInner) -> Self {
-        Self { data }
+    /// Creates telemetry data from a copied buffer row, the header it was read with and the buffer's tick count.
+    pub fn new(data: Vec<u8>, header: Header, tick: i32) -> Self {
+        Self {
+            data: Arc::new(IrDataInner::new(data, header, tick)),
+        }
     }
 
     /// Returns the tick count of the data.
@@ -19,11 +30,97 @@ impl IrData {
         self.data.tick()
     }
 
+    /// Returns the header the data was read with.
+    pub fn header(&self) -> &Header {
+        self.data.header()
+    }
+
     /// Returns the raw data.
     pub fn data(&self) -> &[u8] {
         self.data.data()
     }
 
+    /// Ticks per second of the source, from the header.
+    pub fn tick_rate(&self) -> i32 {
+        self.data.header().tick_rate()
+    }
+
+    /// The wall clock time the buffer was copied.  For records read from an .ibt file, this is computed from the
+    /// start date of the recording, which only has a precision of seconds, and `session_time`.
+    pub fn captured_at(&self) -> SystemTime {
+        self.data.captured_at
+    }
+
+    /// The instant the buffer was copied, or for recordings read from disk.  Only comparable within this process.
+    pub fn captured_instant(&self) -> Instant {
+        self.data.captured
+    }
+
+    /// The `SessionTime` variable, in seconds.  Set by the client and when reading .ibt files.
+    pub fn session_time(&self) -> Option<f64> {
+        self.data.session_time
+    }
+
+    /// Time between the sim signaling new data and the client queueing this packet.  Only known for data sent by a
+    /// live client, it is not stored in recordings.
+    pub fn latency(&self) -> Option<Duration> {
+        self.data.latency
+    }
+
+    /// The variable headers of the session the data was read in.  Set by the client, when reading .ibt files and
+    /// for synthetic telemetry.
+    pub fn var_headers(&self) -> Option<&HashMap<String, VarHeader>> {
+        self.data.var_headers.as_deref()
+    }
+
+    /// Attaches the variable headers describing the data, for `read`.
+    pub fn with_var_headers(mut self, var_headers: Arc<HashMap<String, VarHeader>>) -> Self {
+        self.stamp_var_headers(var_headers);
+        self
+    }
+
+    /// Reads a variable with a typed key from `vars`.  The key is resolved against the attached variable headers
+    /// and the type of the variable is checked before reading.
+    /// ```
+    /// use ir_telemetry::synthetic::SyntheticBuilder;
+    /// use ir_telemetry::vars;
+    ///
+    /// let telemetry = SyntheticBuilder::new(60)
+    ///     .var("Speed", "m/s", "GPS vehicle speed", |_| 42.5f32)
+    ///     .build();
+    /// let data = telemetry.data_at(0);
+    /// assert_eq!(data.read(&vars::SPEED), Some(42.5));
+    /// assert_eq!(data.read(&vars::GEAR), None);
+    /// ```
+    pub fn read<K: Key>(&self, key: &K) -> Option<K::Value> {
+        let variable = key.resolve(self.var_headers()?)?;
+        self.pull_or_none(variable)
+    }
+
+    /// Pairs the data with the variable headers describing it, to serialize every variable by name with serde.
+    /// See `Record`.
+    pub fn to_record<'a>(&'a self, var_headers: &'a HashMap<String, VarHeader>) -> Record<'a> {
+        Record::new(self, var_headers)
+    }
+
+    /// Sets `session_time` from the `SessionTime` variable.
+    pub(crate) fn stamp_session_time(&mut self, session_time: Option<&VarHeader>) {
+        let session_time = self.get::<f64>(session_time);
+        Arc::make_mut(&mut self.data).session_time = session_time;
+    }
+
+    pub(crate) fn stamp_var_headers(&mut self, var_headers: Arc<HashMap<String, VarHeader>>) {
+        Arc::make_mut(&mut self.data).var_headers = Some(var_headers);
+    }
+
+    pub(crate) fn stamp_captured_at(&mut self, captured_at: SystemTime) {
+        Arc::make_mut(&mut self.data).captured_at = captured_at;
+    }
+
+    pub(crate) fn stamp_latency(&mut self, signaled: Instant) {
+        Arc::make_mut(&mut self.data).latency = Some(signaled.elapsed());
+    }
+
     /// Returns the value.  This requires defining the type of the variable based on the expected value.  
     /// For example, if you want the speed of the car, you would use `get::<f32>(var_header.get("Speed"))`.
     pub fn get<T>(&self, variable: Option<&VarHeader>) -> Option<T>
@@ -38,19 +135,77 @@ impl IrData {
         }
     }
 
+    /// Like `get`, with the reason a variable could not be read: `VariableNotFound` if the variable is not in the
+    /// attached variable headers, `MismatchedType` if its type can not be read as `T` and `ValueOutOfRange` if the
+    /// value does not fit in `T`.
+    /// ```
+    /// use ir_telemetry::synthetic::SyntheticBuilder;
+    /// use ir_telemetry::IrError;
+    ///
+    /// let telemetry = SyntheticBuilder::new(60)
+    ///     .var("Speed", "m/s", "GPS vehicle speed", |_| 42.5f32)
+    ///     .build();
+    /// let data = telemetry.data_at(0);
+    /// assert_eq!(data.try_get::<f32>("Speed").unwrap(), 42.5);
+    /// assert!(matches!(data.try_get::<f32>("RPM"), Err(IrError::VariableNotFound(_))));
+    /// assert!(matches!(data.try_get::<bool>("Speed"), Err(IrError::MismatchedType { .. })));
+    /// ```
+    pub fn try_get<T>(&self, name: &str) -> Result<T>
+    where
+        T: TryFrom<IrValue>,
+        <T as TryFrom<IrValue>>::Error: Into<IrError>,
+    {
+        let variable = self
+            .var_headers()
+            .ok_or(IrError::NoVariableHeaders)?
+            .get(name)
+            .ok_or_else(|| IrError::VariableNotFound(name.to_string()))?;
+        self.convert(variable)
+    }
+
+    fn convert<T>(&self, variable: &VarHeader) -> Result<T>
+    where
+        T: TryFrom<IrValue>,
+        <T as TryFrom<IrValue>>::Error: Into<IrError>,
+    {
+        T::try_from(IrValue::from((variable, self.data.data())))
+            .map_err(|e| e.into().for_variable(variable, std::any::type_name::<T>()))
+    }
+
     fn pull_or_none<T>(&self, variable: &VarHeader) -> Option<T>
     where
         T: TryFrom<IrValue>,
+        <T as TryFrom<IrValue>>::Error: Into<IrError>,
     {
-        match T::try_from(IrValue::from((variable, self.data.data()))) {
+        match self.convert(variable) {
             Ok(value) => Some(value),
-            Err(_) => {
-                log::error!("Error converting value: {:?}", variable.name());
+            Err(error) => {
+                log::error!("Error converting value: {}", error);
                 None
             }
         }
     }
 
+    /// Returns the value with the unit of the variable, to convert to other units.  `None` if the variable is not
+    /// numeric or its unit does not convert.
+    /// ```
+    /// use ir_telemetry::synthetic::SyntheticBuilder;
+    /// use ir_telemetry::units::Unit;
+    ///
+    /// let telemetry = SyntheticBuilder::new(60)
+    ///     .var("OilTemp", "C", "Engine oil temperature", |_| 100f32)
+    ///     .build();
+    /// let var_headers = telemetry.var_headers();
+    /// let oil_temp = telemetry.data_at(0).get_quantity(var_headers.get("OilTemp")).unwrap();
+    /// assert_eq!(oil_temp.to(Unit::Fahrenheit).unwrap().value.round(), 212.);
+    /// ```
+    pub fn get_quantity(&self, variable: Option<&VarHeader>) -> Option<Quantity> {
+        let variable = variable?;
+        let unit = variable.unit_type()?;
+        self.get::<f64>(Some(variable))
+            .map(|value| Quantity::new(value, unit))
+    }
+
     /// Useful for getting data without specifing a primitive type.  
     /// The return type must be able to handle
     /// the basic primitives, like for example  `serde_json::Value`
@@ -283,6 +438,19 @@ impl IrData {
                         .map(|v| v.to_string().into())
                 }
             }
+            SingleVarType::IncidentFlags => {
+                if is_array {
+                    self.pull_or_none::<Vec<IncidentFlags>>(variable).map(|v| {
+                        v.into_iter()
+                            .map(|l| l.to_string())
+                            .collect::<Vec<_>>()
+                            .into()
+                    })
+                } else {
+                    self.pull_or_none::<IncidentFlags>(variable)
+                        .map(|v| v.to_string().into())
+                }
+            }
         }
     }
 }
@@ -1,15 +1,34 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
 use crate::mapped_file::header::Header;
+use crate::mapped_file::var_header::VarHeader;
 
 #[derive(Debug, Clone)]
 pub(crate) struct IrDataInner {
     header: Header,
     tick: i32,
     pub(crate) data: Vec<u8>,
+    pub(crate) captured_at: SystemTime,
+    pub(crate) captured: Instant,
+    pub(crate) session_time: Option<f64>,
+    pub(crate) latency: Option<Duration>,
+    pub(crate) var_headers: Option<Arc<HashMap<String, VarHeader>>>,
 }
 
 impl IrDataInner {
     pub(crate) fn new(data: Vec<u8>, header: Header, tick: i32) -> Self {
-        Self { header, tick, data }
+        Self {
+            header,
+            tick,
+            data,
+            captured_at: SystemTime::now(),
+            captured: Instant::now(),
+            session_time: None,
+            latency: None,
+            var_headers: None,
+        }
     }
 
     pub(crate) fn header(&self) -> &Header {
@@ -23,9 +42,4 @@ impl IrDataInner {
     pub(crate) fn data(&self) -> &[u8] {
         &self.data
     }
-
-    pub(crate) fn session_info_tick(&self) -> i32 {
-        let header = self.header();
-        header.session_info_update()
-    }
 }
@@ -0,0 +1,115 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use serde::{Serialize, Serializer};
+
+/// A lap or session time, displayed like `1:23.456`.  Times under a minute are displayed as seconds, `23.456`, and
+/// times of an hour or more with hours, `1:02:03.456`.  Serializes as the display string.
+/// ```
+/// use std::time::Duration;
+/// use ir_telemetry::lap_time::LapTime;
+///
+/// assert_eq!(LapTime::from_secs(83.456).unwrap().to_string(), "1:23.456");
+/// assert_eq!(LapTime::from_secs(-1.), None);
+/// assert_eq!(LapTime(Duration::from_millis(9_050)).to_string(), "9.050");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct LapTime(pub Duration);
+
+impl LapTime {
+    /// A time in seconds, as iRacing stores them.  `None` for the `-1` "no time" value and other negative or
+    /// invalid times.
+    pub fn from_secs(secs: f64) -> Option<Self> {
+        Duration::try_from_secs_f64(secs).ok().map(LapTime)
+    }
+
+    pub fn as_duration(&self) -> Duration {
+        self.0
+    }
+}
+
+impl From<Duration> for LapTime {
+    fn from(duration: Duration) -> Self {
+        LapTime(duration)
+    }
+}
+
+impl From<LapTime> for Duration {
+    fn from(lap_time: LapTime) -> Self {
+        lap_time.0
+    }
+}
+
+impl Display for LapTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Rounded to the millisecond, like the sim does.
+        let millis = (self.0.as_secs_f64() * 1000.).round() as u64;
+        let (hours, minutes) = (millis / 3_600_000, millis / 60_000 % 60);
+        let (seconds, millis) = (millis / 1000 % 60, millis % 1000);
+        if hours > 0 {
+            write!(f, "{}:{:02}:{:02}.{:03}", hours, minutes, seconds, millis)
+        } else if minutes > 0 {
+            write!(f, "{}:{:02}.{:03}", minutes, seconds, millis)
+        } else {
+            write!(f, "{}.{:03}", seconds, millis)
+        }
+    }
+}
+
+impl Serialize for LapTime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Formats a time like `1:23.456`.  See `LapTime`.
+pub fn format_lap_time(duration: Duration) -> String {
+    LapTime(duration).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::IrValue;
+
+    #[test]
+    fn test_lap_time_format() {
+        assert_eq!(
+            format_lap_time(Duration::from_secs_f64(83.4564)),
+            "1:23.456"
+        );
+        assert_eq!(
+            format_lap_time(Duration::from_secs_f64(59.9996)),
+            "1:00.000"
+        );
+        assert_eq!(format_lap_time(Duration::from_secs(3723)), "1:02:03.000");
+        assert_eq!(format_lap_time(Duration::ZERO), "0.000");
+    }
+
+    #[test]
+    fn test_durations_from_values() {
+        assert_eq!(
+            Duration::try_from(IrValue::Float(1.5)).unwrap(),
+            Duration::from_millis(1500)
+        );
+        // iRacing writes -1 to lap times until there is a time.
+        assert!(Duration::try_from(IrValue::Float(-1.)).is_err());
+        assert!(Duration::try_from(IrValue::Int(1)).is_err());
+        assert_eq!(
+            Option::<Duration>::try_from(IrValue::Float(-1.)).unwrap(),
+            None
+        );
+        assert_eq!(
+            Option::<Duration>::try_from(IrValue::Double(-0.5)).unwrap(),
+            None
+        );
+        assert_eq!(
+            Vec::<Option<Duration>>::try_from(IrValue::Array(vec![
+                IrValue::Float(-1.),
+                IrValue::Double(90.),
+            ]))
+            .unwrap(),
+            vec![None, Some(Duration::from_secs(90))]
+        );
+    }
+}
@@ -6,20 +6,47 @@
 //! The other is session data which is stored in a yaml formatted string in the memory mapped file. This is updated at varying intervals.  
 //!
 //! This crate abstracts the unsafe code and provides a safe interface with copied and owned data.  
-//! Users of this crate recieve updates over a channel.  The updates are in the form of `UpdatePacket`.
-//! The `UpdatePacket` enum has four variants:
+//! The memory mapped file is only available on Windows.  The source of the telemetry is abstracted by the
+//! `TelemetrySource` trait, so the crate builds on any platform and other backends can be used with the `Client`.  
+//! `DumpFile` reads a snapshot of the memory mapped file from disk (see the `create_datadump` feature), which is useful
+//! for testing without a running sim.  
+//! `IbtFile` reads the .ibt disk telemetry files written by iRacing.  `IbtWriter` records telemetry from the `Client` to
+//! the same format.  `Client::replay` plays back either kind of recording in real time, or faster, over the same channel.  
+//! `synthetic::SyntheticBuilder` scripts telemetry and session info for tests, offline or through `Client::connect_with`.  
+//! Users of this crate recieve updates over a channel.  The updates are in the form of `UpdatePacket`.  
+//! `ClientBuilder` configures the update rate, the channel capacity, what happens when the receiver falls behind
+//! (`Backpressure`), the wait between reconnects (`Backoff`) and whether every tick is sent (`CaptureMode`).  
+//! Connecting returns a `ClientHandle`, which receives the updates and stops the client with `stop()` or when dropped.  
+//! With the `async` feature, `Client::connect_async` returns a `ClientStream` that yields the updates as a `Stream`.  
+//! A `Hub` shares one client between many subscribers, each with its own update rate and packet filter (`Subscription`).  
+//! `vars` has typed keys for the documented variables, like `vars::SPEED`, read with `IrData::read`.  
+//! `VarHandle` resolves a variable once and reads it every update without allocating, arrays into a slice.  
+//! `TelemetrySource::with_latest` lends the most recent buffer to a closure as a `DataView`, without copying it.  
+//! `IrData::to_record` serializes every variable by name with serde, with enums and flags by name.  
+//! `units` parses the units of variables and session info measurements and converts between them, like `m/s` to `mph`.  
+//! Time variables (`VarHeader::is_time`) read as `Duration`, or `Option<Duration>` for lap times that are not set, and
+//! `lap_time::LapTime` displays them like `1:23.456`.  
+//! Codes of the SDK enums that the crate does not know, like new track surfaces, are kept as `Unknown(code)` instead of
+//! failing the conversion, and bitfields keep unknown bits.  
+//! With the `derive` feature, `#[derive(Telemetry)]` reads the variables named by the fields of a struct with `Telemetry::extract`.  
+//! The main variants of the `UpdatePacket` enum are:
 //! - `Data(IrData)`: This is the telemetry data.  This is sent at the interval specified when connecting to iRacing.
 //! - `SessionInfo(String)`: This is the session info.  This is sent when the session info is updated.  This is checked each data update interval.
-//! This string is in yaml format.  Since the data in session info changes, it is kept as a string to aid in custom deserialization.
-//! A struct `Session` is provided to deserialize this string.  This struct is not exhaustive and is recommended to use a custom deserialization method.
+//!   This string is in yaml format.  Since the data in session info changes, it is kept as a string to aid in custom deserialization.
+//!   A struct `Session` is provided to deserialize this string.  This struct is not exhaustive and is recommended to use a custom deserialization method.
 //! - `VariableHeaders(HashMap<String, VarHeader>)`: This is the variable headers.  The variables in the session are different car to car, but they remain
-//! the same for the session.  Therefore, this packet is sent when each session is loaded.  This is useful for getting the data types and units of the variables.
-//! - `NotConnected`: This is sent when the session is not connected.  This can be useful for reseting display data between sessions.
+//!   the same for the session.  Therefore, this packet is sent when each session is loaded.  This is useful for getting the data types and units of the variables.
+//! - `Disconnected { reason }`: This is sent when the session is not connected.  The `DisconnectReason` tells if the sim is not running, no session is loaded
+//!   or reading failed.  This can be useful for reseting display data between sessions.
+//! - `Connected { tick_rate, header_version }`, `SessionChanged { sub_session_id, session_num }` and `VariableHeadersChanged` follow the lifecycle of the sim.
+//! - `SkippedTicks(u32)`: With `CaptureMode::EveryTick`, the number of ticks the client could not read before the next `Data`.
 //! ```
 //!
 //! use std::collections::HashMap;
 //! use ir_telemetry::{Client, UpdatePacket, IrData, VarHeader};
 //! fn main() {
+//!     # #[cfg(windows)]
+//!     # {
 //!     println!("Starting iRacing Telemetry.  Ensure iRacing is running");
 //!
 //!     // Request updates two times a second. Can do up to 60 updates/ second
@@ -31,7 +58,7 @@
 //!         match packet {
 //!             UpdatePacket::Data(data) => handle_data_update(&data, &var_headers),
 //!             UpdatePacket::SessionInfo(session) => println!("Obtained session data!"),
-//!             UpdatePacket::NotConnected => println!("Session is not open"),
+//!             UpdatePacket::Disconnected { reason } => println!("Not connected: {}", reason),
 //!             UpdatePacket::VariableHeaders(v_header) => {
 //!                 println!("# of variables in this session: {:?}", v_header.len());
 //!                 var_headers = v_header;
@@ -40,6 +67,7 @@
 //!         }
 //!         
 //!     }
+//!     # }
 //!}
 //!
 //! fn handle_data_update(data: &IrData, var_headers: &HashMap<String, VarHeader>) {
@@ -55,22 +83,47 @@
 //!     );
 //! }
 //! ```
+// The crate level example mirrors the README, which needs a `main`.
+#![allow(clippy::needless_doctest_main)]
 
 mod error;
 pub mod types;
+pub mod units;
 pub mod mapped_file {
+    pub mod dumpfile;
     pub mod full_value;
-    pub(crate) mod header;
-    pub(crate) mod memfile;
-    pub(crate) mod values;
+    pub mod header;
+    #[cfg(windows)]
+    pub mod memfile;
+    pub mod values;
     pub mod var_header;
 }
 
 pub(crate) mod utils;
 
+pub mod builder;
+pub mod channel;
 pub mod client;
+pub mod data_view;
+pub mod diagnostics;
+pub mod handle;
+pub mod hub;
+pub mod ibt {
+    pub mod disk_header;
+    pub mod file;
+    pub mod writer;
+}
 pub mod ir_data;
 pub(crate) mod ir_data_inner;
+pub mod lap_time;
+pub mod record;
+pub mod replay;
+pub mod source;
+#[cfg(feature = "async")]
+pub mod stream;
+pub mod synthetic;
+pub mod telemetry;
+pub mod vars;
 pub mod session_info {
     pub mod camera_info;
     pub mod driver_info;
@@ -88,8 +141,28 @@ pub mod session_info {
     pub mod weekend_info;
 }
 
-pub use client::{Client, UpdatePacket};
+pub use builder::{Backoff, CaptureMode, ClientBuilder};
+pub use channel::{Backpressure, Receiver};
+pub use client::{Client, ClientHandle, DisconnectReason, UpdatePacket};
+pub use data_view::DataView;
+pub use diagnostics::Diagnostics;
+pub use error::IrError;
+pub use handle::VarHandle;
+pub use hub::{Hub, PacketFilter, Subscription};
+pub use ibt::file::IbtFile;
+pub use ibt::writer::IbtWriter;
 pub use ir_data::IrData;
+#[cfg(feature = "derive")]
+pub use ir_telemetry_derive::Telemetry;
+pub use mapped_file::dumpfile::DumpFile;
+#[cfg(windows)]
+pub use mapped_file::memfile::FileMap;
 pub use mapped_file::var_header::VarHeader;
+pub use record::Record;
+pub use replay::{Recording, ReplayHandle};
 pub use session_info::session::Session;
+pub use source::TelemetrySource;
+#[cfg(feature = "async")]
+pub use stream::ClientStream;
+pub use telemetry::{OptionalField, Telemetry, TelemetryField};
 pub use types::*;
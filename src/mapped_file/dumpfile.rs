@@ -0,0 +1,105 @@
+use std::path::Path;
+use std::time::Duration;
+
+use crate::data_view::DataView;
+use crate::diagnostics::Diagnostics;
+use crate::error::{IrError, Result};
+use crate::ir_data::IrData;
+use crate::mapped_file::header::Header;
+use crate::source::{read_buffers_after, read_latest_buffer, view_latest_buffer, TelemetrySource};
+use crate::utils::byte_array_to_rust_string;
+
+use super::var_header::{parse_var_headers, VarHeader, VarHeaderData};
+
+/// A snapshot of the iRacing memory mapped file read back from disk, like the `iracing_dumpfile.dat` created with
+/// the `create_datadump` feature.  The dump has the same layout as the live memory map, so it is read the same way.
+/// Since a dump is a single snapshot, the same data is returned on each update.
+#[derive(Debug, Clone)]
+pub struct DumpFile {
+    bytes: Vec<u8>,
+}
+
+impl DumpFile {
+    /// Reads a dump file from disk.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_bytes(std::fs::read(path)?)
+    }
+
+    /// Uses an in memory copy of the memory mapped file.  Fails if the bytes do not start with a valid header.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+        Header::parse(&bytes)?;
+        Ok(Self { bytes })
+    }
+
+    fn slice(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        offset
+            .checked_add(len)
+            .and_then(|end| self.bytes.get(offset..end))
+            .ok_or(IrError::OutOfBounds {
+                offset,
+                len,
+                size: self.bytes.len(),
+            })
+    }
+}
+
+impl TelemetrySource for DumpFile {
+    fn header(&self) -> Result<Header> {
+        Header::parse(&self.bytes)
+    }
+
+    /// Nothing changes in a dump, so this only waits out the timeout.
+    fn wait_for_update(&self, timeout: Duration) -> bool {
+        std::thread::sleep(timeout);
+        false
+    }
+
+    fn get_new_data(&self) -> Result<IrData> {
+        // A dump never changes, so reads are never torn and there is nothing to count.
+        read_latest_buffer(
+            || self.header(),
+            |offset, len| self.slice(offset, len).map(<[u8]>::to_vec),
+            &Diagnostics::default(),
+        )
+    }
+
+    /// Lends the buffer of the dump without copying it.
+    fn with_latest<R, F>(&self, f: F) -> Result<R>
+    where
+        F: FnMut(&DataView<'_>) -> R,
+    {
+        view_latest_buffer(
+            || self.header(),
+            |offset, len| self.slice(offset, len),
+            f,
+            &Diagnostics::default(),
+        )
+    }
+
+    fn get_buffered_data(&self, after_tick: i32) -> Result<Vec<IrData>> {
+        read_buffers_after(
+            || self.header(),
+            |offset, len| self.slice(offset, len).map(<[u8]>::to_vec),
+            after_tick,
+            &Diagnostics::default(),
+        )
+    }
+
+    fn get_var_headers(&self) -> Result<Vec<VarHeader>> {
+        let header = self.header()?;
+        let bytes = self.slice(
+            header.var_header_offset as usize,
+            header.num_vars as usize * std::mem::size_of::<VarHeaderData>(),
+        )?;
+        parse_var_headers(&header, bytes)
+    }
+
+    fn session_info(&self) -> Result<String> {
+        let header = self.header()?;
+        let slice = self.slice(
+            header.session_info_offset as usize,
+            header.session_info_len as usize,
+        )?;
+        Ok(byte_array_to_rust_string(slice))
+    }
+}
@@ -19,6 +19,7 @@ pub enum SingleVarType {
     CameraState,
     PitServiceFlags,
     PaceFlags,
+    IncidentFlags,
 }
 
 #[derive(Debug, Clone, Copy, Serialize)]
@@ -1,8 +1,13 @@
 use bitflags::bitflags;
+
+use crate::error::{IrError, Result};
+
 const MAX_BUFFERS: usize = 4;
+/// The api header version written by this crate.
+pub(crate) const IRSDK_VER: i32 = 2;
 
 #[repr(C)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub(crate) struct Buffer {
     pub(crate) tick_count: i32,
     pub(crate) offset: i32, // From the header
@@ -10,15 +15,17 @@ pub(crate) struct Buffer {
 }
 
 bitflags! {
-    #[derive(Debug, Copy, Clone, PartialEq)]
+    #[derive(Debug, Copy, Clone, PartialEq, Default)]
     struct StatusField:i32 {
         const CONNECTED = 1;
     }
 }
 
+/// The header at the start of the iRacing memory mapped file.  Describes where the session info, variable headers
+/// and telemetry buffers are located.
 #[repr(C)]
-#[derive(Debug, Copy, Clone)]
-pub(crate) struct Header {
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Header {
     ver: i32,                  // this api header version, see IRSDK_VER
     status: StatusField,       // bitfield using irsdk_StatusField
     pub(crate) tick_rate: i32, // ticks per second (60 or 360 etc)
@@ -38,17 +45,255 @@ pub(crate) struct Header {
 }
 
 impl Header {
-    pub(crate) fn is_connected(&self) -> bool {
+    /// A connected header with all offsets and lengths set to zero.
+    pub(crate) fn connected(tick_rate: i32) -> Self {
+        Self {
+            ver: IRSDK_VER,
+            status: StatusField::CONNECTED,
+            tick_rate,
+            ..Default::default()
+        }
+    }
+
+    /// Serializes the header in the layout of the memory mapped file.
+    pub(crate) fn to_bytes(self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(std::mem::size_of::<Header>());
+        for value in [
+            self.ver,
+            self.status.bits(),
+            self.tick_rate,
+            self.session_info_update,
+            self.session_info_len,
+            self.session_info_offset,
+            self.num_vars,
+            self.var_header_offset,
+            self.num_buf,
+            self.buf_len,
+        ]
+        .into_iter()
+        .chain(self.pad1)
+        {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        for buffer in &self.var_buf {
+            for value in [buffer.tick_count, buffer.offset]
+                .into_iter()
+                .chain(buffer.pad)
+            {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Parses a header from the start of `bytes`.  Fails if the version is not supported, the number of buffers
+    /// is out of range or an offset or length is negative.
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let size = std::mem::size_of::<Header>();
+        if bytes.len() < size {
+            return Err(IrError::OutOfBounds {
+                offset: 0,
+                len: size,
+                size: bytes.len(),
+            });
+        }
+        let field = |index: usize| {
+            let start = index * 4;
+            i32::from_le_bytes([
+                bytes[start],
+                bytes[start + 1],
+                bytes[start + 2],
+                bytes[start + 3],
+            ])
+        };
+        let mut var_buf = [Buffer::default(); MAX_BUFFERS];
+        for (i, buffer) in var_buf.iter_mut().enumerate() {
+            // The buffers start after the 12 leading fields and are 4 fields each.
+            let first = 12 + i * 4;
+            *buffer = Buffer {
+                tick_count: field(first),
+                offset: field(first + 1),
+                pad: [field(first + 2), field(first + 3)],
+            };
+        }
+        let header = Self {
+            ver: field(0),
+            status: StatusField::from_bits_retain(field(1)),
+            tick_rate: field(2),
+            session_info_update: field(3),
+            session_info_len: field(4),
+            session_info_offset: field(5),
+            num_vars: field(6),
+            var_header_offset: field(7),
+            num_buf: field(8),
+            buf_len: field(9),
+            pad1: [field(10), field(11)],
+            var_buf,
+        };
+        header.validate()?;
+        Ok(header)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !(1..=IRSDK_VER).contains(&self.ver) {
+            return Err(IrError::UnsupportedVersion(self.ver));
+        }
+        if !(1..=MAX_BUFFERS as i32).contains(&self.num_buf) {
+            return Err(IrError::InvalidHeader(format!(
+                "{} buffers, expected 1 to {}",
+                self.num_buf, MAX_BUFFERS
+            )));
+        }
+        for (name, value) in [
+            ("session info length", self.session_info_len),
+            ("session info offset", self.session_info_offset),
+            ("variable count", self.num_vars),
+            ("variable header offset", self.var_header_offset),
+            ("buffer length", self.buf_len),
+        ]
+        .into_iter()
+        .chain(self.buffers().iter().map(|b| ("buffer offset", b.offset)))
+        {
+            if value < 0 {
+                return Err(IrError::InvalidHeader(format!(
+                    "negative {}: {}",
+                    name, value
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(any(windows, test))]
+    /// Checks that the session info, variable headers and buffers lie within `size` bytes, the size of the
+    /// memory mapped file.
+    pub(crate) fn check_bounds(&self, size: usize) -> Result<()> {
+        let var_headers_len = self.num_vars as usize
+            * std::mem::size_of::<crate::mapped_file::var_header::VarHeaderData>();
+        [
+            (
+                self.session_info_offset as usize,
+                self.session_info_len as usize,
+            ),
+            (self.var_header_offset as usize, var_headers_len),
+        ]
+        .into_iter()
+        .chain(
+            self.buffers()
+                .iter()
+                .map(|b| (b.offset as usize, self.buf_len as usize)),
+        )
+        .try_for_each(|(offset, len)| {
+            if offset.checked_add(len).is_none_or(|end| end > size) {
+                Err(IrError::OutOfBounds { offset, len, size })
+            } else {
+                Ok(())
+            }
+        })
+    }
+
+    /// The buffers in use.
+    fn buffers(&self) -> &[Buffer] {
+        &self.var_buf[..(self.num_buf.max(1) as usize).min(MAX_BUFFERS)]
+    }
+
+    /// The version of the api header.
+    pub fn version(&self) -> i32 {
+        self.ver
+    }
+
+    /// Ticks per second (60 or 360 etc).
+    pub fn tick_rate(&self) -> i32 {
+        self.tick_rate
+    }
+
+    /// Returns true if the sim reports an active session.
+    pub fn is_connected(&self) -> bool {
         self.status.contains(StatusField::CONNECTED)
     }
 
-    pub(crate) fn most_recent_buffer(&self) -> &Buffer {
-        assert!(!self.var_buf.is_empty()); // Something is wrong.
-                                           // Unwrap is save as we have at least one buffer.
-        self.var_buf.iter().max_by_key(|b| b.tick_count).unwrap()
+    /// Index in `var_buf` of the buffer with the highest tick count.
+    pub(crate) fn most_recent_buffer_index(&self) -> usize {
+        // Unwrap is safe as there is always at least one buffer.
+        self.buffers()
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, b)| b.tick_count)
+            .map(|(i, _)| i)
+            .unwrap()
+    }
+
+    /// Indices in `var_buf` of the buffers in use, oldest tick first.
+    pub(crate) fn buffer_indices_by_tick(&self) -> Vec<usize> {
+        let mut indices = (0..self.buffers().len()).collect::<Vec<_>>();
+        indices.sort_by_key(|&i| self.var_buf[i].tick_count);
+        indices
     }
 
-    pub(crate) fn session_info_update(&self) -> i32 {
+    /// Incremented each time the session info changes.
+    pub fn session_info_update(&self) -> i32 {
         self.session_info_update
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header() -> Header {
+        let mut header = Header::connected(60);
+        header.session_info_offset = 112;
+        header.session_info_len = 100;
+        header.num_vars = 2;
+        header.var_header_offset = 212;
+        header.num_buf = 2;
+        header.buf_len = 16;
+        header.var_buf[0].offset = 500;
+        header.var_buf[1].offset = 516;
+        header
+    }
+
+    #[test]
+    fn test_parse_round_trips() {
+        let parsed = Header::parse(&header().to_bytes()).unwrap();
+        assert_eq!(parsed.version(), IRSDK_VER);
+        assert!(parsed.is_connected());
+        assert_eq!(parsed.num_buf, 2);
+        assert_eq!(parsed.var_buf[1].offset, 516);
+        assert!(parsed.check_bounds(532).is_ok());
+        assert!(matches!(
+            parsed.check_bounds(531),
+            Err(IrError::OutOfBounds { offset: 516, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_headers() {
+        let bytes = header().to_bytes();
+        assert!(matches!(
+            Header::parse(&bytes[..100]),
+            Err(IrError::OutOfBounds { .. })
+        ));
+
+        let mut bytes = header().to_bytes();
+        bytes[0..4].copy_from_slice(&3i32.to_le_bytes());
+        assert!(matches!(
+            Header::parse(&bytes),
+            Err(IrError::UnsupportedVersion(3))
+        ));
+
+        let mut invalid = header();
+        invalid.num_buf = MAX_BUFFERS as i32 + 1;
+        assert!(matches!(
+            Header::parse(&invalid.to_bytes()),
+            Err(IrError::InvalidHeader(_))
+        ));
+
+        let mut invalid = header();
+        invalid.var_buf[1].offset = -1;
+        assert!(matches!(
+            Header::parse(&invalid.to_bytes()),
+            Err(IrError::InvalidHeader(_))
+        ));
+    }
+}
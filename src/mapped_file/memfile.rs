@@ -1,29 +1,37 @@
 use std::time::Duration;
 
-use windows::Win32::Foundation::{CloseHandle, HANDLE};
+use windows::Win32::Foundation::{CloseHandle, HANDLE, WAIT_OBJECT_0};
 
-use windows::Win32::System::Memory::{UnmapViewOfFile, MEMORY_MAPPED_VIEW_ADDRESS};
+use windows::Win32::System::Memory::{
+    UnmapViewOfFile, VirtualQuery, MEMORY_BASIC_INFORMATION, MEMORY_MAPPED_VIEW_ADDRESS,
+};
 use windows::Win32::System::{
     Memory::{self, MapViewOfFile, OpenFileMappingA},
     Threading::{OpenEventA, WaitForSingleObject, SYNCHRONIZATION_SYNCHRONIZE},
 };
 use windows_core::{s, PCSTR};
 
+use crate::data_view::DataView;
+use crate::diagnostics::Diagnostics;
 use crate::error::{IrError, Result};
-use crate::ir_data_inner::IrDataInner;
+use crate::ir_data::IrData;
 use crate::mapped_file::header::Header;
+use crate::source::{read_buffers_after, read_latest_buffer, view_latest_buffer, TelemetrySource};
 use crate::utils::byte_array_to_rust_string;
 
-use super::var_header::VarHeaderData;
+use super::var_header::{parse_var_headers, VarHeader, VarHeaderData};
 
 const MEM_MAP_FILENAME: PCSTR = s!("Local\\IRSDKMemMapFileName");
 const DATA_EVENT_NAME: PCSTR = s!("Local\\IRSDKDataValidEvent");
 
+/// The live iRacing memory mapped file.  Only available on Windows.
 #[derive(Debug)]
-pub(crate) struct FileMap {
+pub struct FileMap {
     mapping: HANDLE,
     shared_mem: MEMORY_MAPPED_VIEW_ADDRESS,
+    shared_mem_size: usize,
     data_event: HANDLE,
+    diagnostics: Diagnostics,
 }
 
 impl Drop for FileMap {
@@ -39,19 +47,51 @@ impl Drop for FileMap {
 
 // Implementation of FileMap
 impl FileMap {
-    pub(crate) fn new() -> Result<Self> {
+    /// Opens the memory mapped file and the data valid event.  Fails if iRacing is not running.
+    pub fn new() -> Result<Self> {
+        Self::with_diagnostics(Diagnostics::new())
+    }
+
+    /// Like `new`, counting read problems in `diagnostics`.
+    pub fn with_diagnostics(diagnostics: Diagnostics) -> Result<Self> {
         //Opens file mapping object
         // IRSDK: hMemMapFile = OpenFileMapping( FILE_MAP_READ, FALSE, IRSDK_MEMMAPFILENAME);
         // https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-openfilemappinga#remarks
         let access = Memory::FILE_MAP_READ;
         let mapping = unsafe {
             OpenFileMappingA(access.0, false, MEM_MAP_FILENAME)
-                .map_err(|e| IrError::MemMappingError(e.to_string()))?
+                .map_err(|e| IrError::MemMappingError(e.into()))?
         };
 
         // Create File View
         // IRSDK: pSharedMem = (const char *)MapViewOfFile(hMemMapFile, FILE_MAP_READ, 0, 0, 0);
         let shared_mem = unsafe { MapViewOfFile(mapping, access, 0, 0, 0) };
+        if shared_mem.Value.is_null() {
+            let error = windows_core::Error::from_win32();
+            unsafe {
+                let _ = CloseHandle(mapping);
+            }
+            return Err(IrError::MemMappingError(error.into()));
+        }
+
+        // The view covers the whole mapping, so its region size bounds every read.
+        let mut info = MEMORY_BASIC_INFORMATION::default();
+        let queried = unsafe {
+            VirtualQuery(
+                Some(shared_mem.Value as *const _),
+                &mut info,
+                std::mem::size_of::<MEMORY_BASIC_INFORMATION>(),
+            )
+        };
+        if queried == 0 {
+            let error = windows_core::Error::from_win32();
+            unsafe {
+                let _ = UnmapViewOfFile(shared_mem);
+                let _ = CloseHandle(mapping);
+            }
+            return Err(IrError::MemMappingError(error.into()));
+        }
+        let shared_mem_size = info.RegionSize;
 
         #[cfg(feature = "create_datadump")]
         {
@@ -59,28 +99,16 @@ impl FileMap {
             use std::io::Write;
             log::info!("Creating dumpfile");
 
-            let buf_count = 3usize;
-            let max_vars = 4096usize;
-            let buf_length = max_vars * 6usize;
-            let session_str_len: usize = 131072; //128k
-
-            let shared_mem_size = std::mem::size_of::<Header>()
-                + session_str_len
-                + (max_vars * std::mem::size_of::<VarHeaderData>())
-                + (buf_count * buf_length);
-
+            // Dump everything up to the end of the last section, so the var buffers can be read back with `DumpFile`.
             let mut file = File::create("iracing_dumpfile.dat").unwrap();
             let data: &[u8] = unsafe {
                 std::slice::from_raw_parts(shared_mem.Value as *const u8, shared_mem_size)
             };
 
             // This is not normally configured, so unwraping and panicking is fine. We want to know something is wrong.
-            file.write(data).unwrap();
+            // The view stays mapped for the returned `FileMap`, which releases it when dropped.
+            file.write_all(data).unwrap();
             file.flush().unwrap();
-            unsafe {
-                let _ = UnmapViewOfFile(shared_mem);
-                let _ = CloseHandle(mapping);
-            }
             log::info!("Dumpfile created");
         }
 
@@ -89,7 +117,7 @@ impl FileMap {
             OpenEventA(SYNCHRONIZATION_SYNCHRONIZE, false, DATA_EVENT_NAME).map_err(|e| {
                 let _ = UnmapViewOfFile(shared_mem);
                 let _ = CloseHandle(mapping);
-                IrError::MemMappingError(e.to_string())
+                IrError::MemMappingError(e.into())
             })?
         };
 
@@ -97,21 +125,64 @@ impl FileMap {
         Ok(Self {
             mapping,
             shared_mem,
+            shared_mem_size,
             data_event,
+            diagnostics,
         })
     }
 
-    pub(crate) fn header(&self) -> Header {
-        // Safety: The header is a valid pointer to a Header struct
-        // Using read_volatile to ensure we get the latest data and optimazation does not remove the read.
-        unsafe { std::ptr::read_volatile(self.shared_mem.Value as *const Header) }
+    /// Counters of read problems, like torn buffer reads.
+    pub fn diagnostics(&self) -> &Diagnostics {
+        &self.diagnostics
     }
+}
 
-    pub(crate) fn is_connected(&self) -> bool {
-        self.header().is_connected()
+impl FileMap {
+    /// Borrows `len` bytes at `offset` of the mapping.  The sim keeps writing to the mapping, so the bytes can change
+    /// while they are borrowed.
+    fn slice(&self, offset: usize, len: usize) -> Result<&[u8]> {
+        if offset
+            .checked_add(len)
+            .is_none_or(|end| end > self.shared_mem_size)
+        {
+            return Err(IrError::OutOfBounds {
+                offset,
+                len,
+                size: self.shared_mem_size,
+            });
+        }
+        // Safety: The range was checked to be within the mapped view above, which lives as long as `self`.
+        Ok(unsafe {
+            std::slice::from_raw_parts(self.shared_mem.Value.add(offset) as *const u8, len)
+        })
+    }
+
+    /// Copies `len` bytes at `offset` out of the mapping.
+    fn copy(&self, offset: usize, len: usize) -> Result<Vec<u8>> {
+        self.slice(offset, len).map(<[u8]>::to_vec)
+    }
+}
+
+impl TelemetrySource for FileMap {
+    fn header(&self) -> Result<Header> {
+        const HEADER_SIZE: usize = std::mem::size_of::<Header>();
+        if self.shared_mem_size < HEADER_SIZE {
+            return Err(IrError::OutOfBounds {
+                offset: 0,
+                len: HEADER_SIZE,
+                size: self.shared_mem_size,
+            });
+        }
+        // Safety: The mapping is at least the size of a header, which is read as plain bytes.
+        // Using read_volatile to ensure we get the latest data and optimazation does not remove the read.
+        let bytes =
+            unsafe { std::ptr::read_volatile(self.shared_mem.Value as *const [u8; HEADER_SIZE]) };
+        let header = Header::parse(&bytes)?;
+        header.check_bounds(self.shared_mem_size)?;
+        Ok(header)
     }
 
-    pub(crate) fn wait_for_update(&self, timeout: Duration) {
+    fn wait_for_update(&self, timeout: Duration) -> bool {
         // timout in milliseconds returns a u128, and windows call expects a u32.
         let milliseconds = u32::try_from(timeout.as_millis()).unwrap_or_else(|_| {
             log::warn!(
@@ -120,51 +191,59 @@ impl FileMap {
             );
             u32::MAX
         });
-        unsafe {
-            WaitForSingleObject(self.data_event, milliseconds);
-        }
+        unsafe { WaitForSingleObject(self.data_event, milliseconds) == WAIT_OBJECT_0 }
     }
 
     /// Since the backing data is volatile, we will grab the header and copy from the buffer.
-    /// This allows the caller to own the data at this point.
-    pub(crate) fn get_new_data(&self) -> IrDataInner {
-        let header = self.header();
-        let buffer = header.most_recent_buffer();
-        let buffer_len = header.buf_len as usize;
-        let offset = buffer.offset as usize;
-        let data_pointer = unsafe { self.shared_mem.Value.add(offset) };
-
-        // Safety: Data is aligned, buffer is valid, and lifetime does not exceed the scope of this function. Data is copied into a new Vec.
-        let data: &[u8] =
-            unsafe { std::slice::from_raw_parts(data_pointer as *const u8, buffer_len) };
-        // Copy the date and return an owned copy.  Gets us out of the unsafe code for the rest of the data's usage.
-        IrDataInner::new(data.to_vec(), header, buffer.tick_count)
+    /// This allows the caller to own the data at this point.  The copy is retried if the sim overwrote the buffer
+    /// while it was being copied.
+    fn get_new_data(&self) -> Result<IrData> {
+        read_latest_buffer(
+            || self.header(),
+            |offset, len| self.copy(offset, len),
+            &self.diagnostics,
+        )
     }
 
-    pub(crate) fn get_var_headers<'a, T>(&'a self) -> Vec<T>
+    /// Lends the buffer straight from the mapping, checking the tick count after `f` like `get_new_data` does after
+    /// the copy.
+    fn with_latest<R, F>(&self, f: F) -> Result<R>
     where
-        T: From<&'a VarHeaderData>,
+        F: FnMut(&DataView<'_>) -> R,
     {
-        let header = self.header();
-        let offset = header.var_header_offset as usize;
-        let num_vars = header.num_vars as usize;
-
-        let slice = unsafe {
-            std::slice::from_raw_parts(
-                self.shared_mem.Value.add(offset) as *const VarHeaderData,
-                num_vars - 1,
-            )
-        };
-        slice.iter().map(|vh| T::from(vh)).collect()
+        view_latest_buffer(
+            || self.header(),
+            |offset, len| self.slice(offset, len),
+            f,
+            &self.diagnostics,
+        )
     }
 
-    pub(crate) fn session_info(&self) -> String {
-        let header = self.header();
-        let offset = header.session_info_offset as usize;
-        let len = header.session_info_len as usize;
-        let slice = unsafe {
-            std::slice::from_raw_parts(self.shared_mem.Value.add(offset) as *const u8, len)
-        };
-        byte_array_to_rust_string(slice)
+    /// Copies the buffers the sim wrote since `after_tick`, oldest first.
+    fn get_buffered_data(&self, after_tick: i32) -> Result<Vec<IrData>> {
+        read_buffers_after(
+            || self.header(),
+            |offset, len| self.copy(offset, len),
+            after_tick,
+            &self.diagnostics,
+        )
+    }
+
+    fn get_var_headers(&self) -> Result<Vec<VarHeader>> {
+        let header = self.header()?;
+        let bytes = self.copy(
+            header.var_header_offset as usize,
+            header.num_vars as usize * std::mem::size_of::<VarHeaderData>(),
+        )?;
+        parse_var_headers(&header, &bytes)
+    }
+
+    fn session_info(&self) -> Result<String> {
+        let header = self.header()?;
+        let bytes = self.copy(
+            header.session_info_offset as usize,
+            header.session_info_len as usize,
+        )?;
+        Ok(byte_array_to_rust_string(&bytes))
     }
 }
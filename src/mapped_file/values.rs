@@ -3,9 +3,10 @@ use crate::types::IrValue;
 use num_derive::FromPrimitive;
 use std::fmt::Display;
 
+/// The type of a variable as stored in the telemetry buffer.
 #[repr(i32)]
 #[derive(Debug, Clone, Copy, PartialEq, FromPrimitive)]
-pub(crate) enum DataVarType {
+pub enum DataVarType {
     Char = 0,
     Bool = 1,
     Int = 2,
@@ -16,6 +17,7 @@ pub(crate) enum DataVarType {
 }
 
 impl DataVarType {
+    /// Size of a single entry, in bytes.
     pub fn amount_of_bytes(&self) -> usize {
         match self {
             DataVarType::Char => 1,
@@ -63,7 +65,7 @@ impl<'a, S, D> From<(&'a VarHeaderGeneric<S, D>, &'a [u8])> for IrValue {
         let offset = var_header.offset as usize;
         let size = var_header._type.amount_of_bytes();
         debug_assert!(
-            offset + size < buffer.len(),
+            offset + size <= buffer.len(),
             "Offset larger than buffer length"
         );
         if count == 1 {
@@ -71,8 +73,9 @@ impl<'a, S, D> From<(&'a VarHeaderGeneric<S, D>, &'a [u8])> for IrValue {
                 DataVarType::Char => {
                     IrValue::Char(value_from_bytes_ptr_offset_count(buffer, offset, 0))
                 }
+                // Read as a byte, any value other than 0 or 1 is not a valid bool.
                 DataVarType::Bool => {
-                    IrValue::Bool(value_from_bytes_ptr_offset_count(buffer, offset, 0))
+                    IrValue::Bool(value_from_bytes_ptr_offset_count::<u8>(buffer, offset, 0) != 0)
                 }
                 DataVarType::Int => {
                     IrValue::Int(value_from_bytes_ptr_offset_count(buffer, offset, 0))
@@ -96,7 +99,7 @@ impl<'a, S, D> From<(&'a VarHeaderGeneric<S, D>, &'a [u8])> for IrValue {
                         value_from_bytes_ptr_offset_count(buffer, offset, size * i),
                     )),
                     DataVarType::Bool => array.push(IrValue::Bool(
-                        value_from_bytes_ptr_offset_count(buffer, offset, size * i),
+                        value_from_bytes_ptr_offset_count::<u8>(buffer, offset, size * i) != 0,
                     )),
                     DataVarType::Int => array.push(IrValue::Int(
                         value_from_bytes_ptr_offset_count(buffer, offset, size * i),
@@ -1,5 +1,10 @@
+use num::FromPrimitive;
+
+use crate::error::{IrError, Result};
 use crate::mapped_file::full_value::VarType;
+use crate::mapped_file::header::Header;
 use crate::mapped_file::values::DataVarType;
+use crate::units::Unit;
 use crate::utils::byte_array_to_rust_string;
 
 use super::full_value::SingleVarType;
@@ -11,7 +16,7 @@ pub type VarHeaderData = VarHeaderGeneric<[u8; MAX_STRING], [u8; MAX_DESC]>;
 pub type VarHeader = VarHeaderGeneric<String, String>;
 
 #[repr(C)]
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct VarHeaderGeneric<S, D> {
     pub(crate) _type: DataVarType, // irsdk_VarType
     pub(crate) offset: i32,        // offset fron start of buffer row
@@ -25,7 +30,130 @@ pub struct VarHeaderGeneric<S, D> {
     pub(crate) unit: S, // something like "kg/m^2"
 }
 
+impl<S, D> VarHeaderGeneric<S, D> {
+    /// Size of the variable in a buffer row, in bytes.
+    pub(crate) fn size(&self) -> usize {
+        self._type.amount_of_bytes() * self.count.max(0) as usize
+    }
+}
+
+impl VarHeaderData {
+    /// Parses a variable header from the start of `bytes`.  Fails if the type code is unknown or the offset or
+    /// count is negative.
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let size = std::mem::size_of::<VarHeaderData>();
+        if bytes.len() < size {
+            return Err(IrError::OutOfBounds {
+                offset: 0,
+                len: size,
+                size: bytes.len(),
+            });
+        }
+        let field = |start: usize| {
+            i32::from_le_bytes([
+                bytes[start],
+                bytes[start + 1],
+                bytes[start + 2],
+                bytes[start + 3],
+            ])
+        };
+        let type_code = field(0);
+        let _type = DataVarType::from_i32(type_code).ok_or(IrError::UnknownVarType(type_code))?;
+        let (offset, count) = (field(4), field(8));
+        if offset < 0 || count < 0 {
+            return Err(IrError::InvalidHeader(format!(
+                "variable with offset {} and count {}",
+                offset, count
+            )));
+        }
+
+        // Unwraps are safe, the length was checked above.
+        let name_start = 16;
+        let desc_start = name_start + MAX_STRING;
+        let unit_start = desc_start + MAX_DESC;
+        Ok(Self {
+            _type,
+            offset,
+            count,
+            count_as_time: bytes[12],
+            pad: [bytes[13], bytes[14], bytes[15]],
+            name: bytes[name_start..desc_start].try_into().unwrap(),
+            desc: bytes[desc_start..unit_start].try_into().unwrap(),
+            unit: bytes[unit_start..unit_start + MAX_STRING]
+                .try_into()
+                .unwrap(),
+        })
+    }
+
+    /// Serializes the variable header in the layout of the memory mapped file.
+    pub(crate) fn to_bytes(self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(std::mem::size_of::<VarHeaderData>());
+        bytes.extend_from_slice(&(self._type as i32).to_le_bytes());
+        bytes.extend_from_slice(&self.offset.to_le_bytes());
+        bytes.extend_from_slice(&self.count.to_le_bytes());
+        bytes.push(self.count_as_time);
+        bytes.extend_from_slice(&self.pad);
+        bytes.extend_from_slice(&self.name);
+        bytes.extend_from_slice(&self.desc);
+        bytes.extend_from_slice(&self.unit);
+        bytes
+    }
+}
+
+/// Parses the `num_vars` variable headers described by `header` from `bytes`, which start at the variable header
+/// offset.  Fails if a variable does not fit in a buffer row.
+pub(crate) fn parse_var_headers(header: &Header, bytes: &[u8]) -> Result<Vec<VarHeader>> {
+    let size = std::mem::size_of::<VarHeaderData>();
+    let num_vars = header.num_vars.max(0) as usize;
+    if bytes.len() < num_vars * size {
+        return Err(IrError::OutOfBounds {
+            offset: 0,
+            len: num_vars * size,
+            size: bytes.len(),
+        });
+    }
+    bytes
+        .chunks_exact(size)
+        .take(num_vars)
+        .map(|bytes| {
+            let var_header = VarHeaderData::parse(bytes)?;
+            let offset = var_header.offset as usize;
+            let len = var_header.size();
+            if offset + len > header.buf_len.max(0) as usize {
+                return Err(IrError::OutOfBounds {
+                    offset,
+                    len,
+                    size: header.buf_len.max(0) as usize,
+                });
+            }
+            Ok(VarHeader::from(&var_header))
+        })
+        .collect()
+}
+
 impl VarHeader {
+    /// Creates a variable header.  `offset` is the offset from the start of a buffer row, in bytes, and `count`
+    /// is the number of entries (greater than one for arrays).
+    pub fn new(
+        _type: DataVarType,
+        offset: i32,
+        count: i32,
+        name: &str,
+        desc: &str,
+        unit: &str,
+    ) -> Self {
+        Self {
+            _type,
+            offset,
+            count,
+            count_as_time: 0,
+            pad: [0; 3],
+            name: name.to_string(),
+            desc: desc.to_string(),
+            unit: unit.to_string(),
+        }
+    }
+
     pub fn name(&self) -> &str {
         self.name.as_str()
     }
@@ -38,6 +166,34 @@ impl VarHeader {
         self.unit.as_str()
     }
 
+    /// The parsed unit of the variable, if it is one that converts, like `m/s`.
+    pub fn unit_type(&self) -> Option<Unit> {
+        Unit::parse(&self.unit)
+    }
+
+    /// Offset of the variable from the start of a buffer row, in bytes.
+    pub fn offset(&self) -> i32 {
+        self.offset
+    }
+
+    /// Number of entries.  Greater than one for arrays.
+    pub fn count(&self) -> i32 {
+        self.count
+    }
+
+    /// Returns true if the variable is a time in seconds, like `SessionTime` or `CarIdxLastLapTime`.  Times that are
+    /// never negative can be read as `Duration`, or `Option<Duration>` for lap times that are `-1` until set.  Signed
+    /// times, like the `LapDelta*` variables, are in seconds too but must be read as `f32`.
+    pub fn is_time(&self) -> bool {
+        self.unit_type() == Some(Unit::Seconds)
+    }
+
+    /// Returns true if the entries of the array are samples over time instead of one per car, like the six 360 Hz
+    /// samples of the `_ST` variables in each update.
+    pub fn counts_as_time(&self) -> bool {
+        self.count_as_time != 0
+    }
+
     pub fn var_type(&self) -> VarType {
         let is_array = self.count > 1;
         let single = match self._type {
@@ -45,12 +201,14 @@ impl VarHeader {
             DataVarType::Bool => SingleVarType::Bool,
             DataVarType::Int => {
                 match self.name.as_str() {
-                    // TODO These seem backwards, but it's whats in in the pdf
-                    "CarIdxTrackSurface" => SingleVarType::TrackLocation,
+                    // The `TrackSurface` variables are locations (irsdk_TrkLoc), the materials are surfaces.
+                    "CarIdxTrackSurface" | "PlayerTrackSurface" => SingleVarType::TrackLocation,
+                    "CarIdxTrackSurfaceMaterial" | "PlayerTrackSurfaceMaterial" => {
+                        SingleVarType::TrackSurface
+                    }
                     "SessionState" => SingleVarType::SessionState,
                     "CarLeftRight" => SingleVarType::CarLeftRight,
-                    // TODO : verify these? Can't find it in the pdf
-                    "PitSvStatus" => SingleVarType::PitStatus,
+                    "PlayerCarPitSvStatus" => SingleVarType::PitStatus,
                     "PaceMode" => SingleVarType::PaceMode,
                     "TrackWetness" => SingleVarType::TrackWetness,
                     _ => SingleVarType::Int,
@@ -64,6 +222,7 @@ impl VarHeader {
                 "CarIdxSessionFlags" | "SessionFlags" => SingleVarType::Flags,
                 "CamCameraState" => SingleVarType::CameraState,
                 "CarIdxPaceFlags" => SingleVarType::PaceFlags,
+                "PlayerIncidents" => SingleVarType::IncidentFlags,
                 _ => {
                     log::error!("Unknown bitfield: {}, using an integer", self.name);
                     SingleVarType::Int
@@ -112,6 +271,29 @@ impl From<&VarHeaderData> for VarHeader {
     }
 }
 
+/// Copies a string into a fixed size, nul terminated field.  Strings that are too long are truncated.
+fn string_to_byte_array<const N: usize>(string: &str) -> [u8; N] {
+    let mut array = [0u8; N];
+    let len = string.len().min(N - 1);
+    array[..len].copy_from_slice(&string.as_bytes()[..len]);
+    array
+}
+
+impl From<&VarHeader> for VarHeaderData {
+    fn from(header: &VarHeader) -> Self {
+        Self {
+            _type: header._type,
+            offset: header.offset,
+            count: header.count,
+            count_as_time: header.count_as_time,
+            pad: header.pad,
+            name: string_to_byte_array(&header.name),
+            desc: string_to_byte_array(&header.desc),
+            unit: string_to_byte_array(&header.unit),
+        }
+    }
+}
+
 impl From<&VarHeaderData> for VarHeaderData {
     fn from(header: &VarHeaderData) -> Self {
         Self {
@@ -126,3 +308,77 @@ impl From<&VarHeaderData> for VarHeaderData {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var_header_bytes(type_code: i32, offset: i32) -> Vec<u8> {
+        let mut bytes = VarHeaderData::from(&VarHeader::new(
+            DataVarType::Float,
+            offset,
+            1,
+            "Speed",
+            "",
+            "m/s",
+        ))
+        .to_bytes();
+        bytes[0..4].copy_from_slice(&type_code.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_parse_validates_type_code() {
+        let parsed = VarHeader::from(VarHeaderData::parse(&var_header_bytes(4, 8)).unwrap());
+        assert_eq!(parsed.name(), "Speed");
+        assert_eq!(parsed.units(), "m/s");
+        assert_eq!(parsed.offset(), 8);
+
+        assert!(matches!(
+            VarHeaderData::parse(&var_header_bytes(42, 8)),
+            Err(IrError::UnknownVarType(42))
+        ));
+    }
+
+    #[test]
+    fn test_parse_var_headers_checks_row_length() {
+        let mut header = Header::connected(60);
+        header.num_vars = 2;
+        header.buf_len = 8;
+        let mut bytes = var_header_bytes(4, 0);
+        bytes.extend(var_header_bytes(4, 4));
+        assert_eq!(parse_var_headers(&header, &bytes).unwrap().len(), 2);
+
+        header.buf_len = 7;
+        assert!(matches!(
+            parse_var_headers(&header, &bytes),
+            Err(IrError::OutOfBounds { offset: 4, .. })
+        ));
+    }
+
+    #[test]
+    fn test_var_type_maps_sdk_types() {
+        let var_type =
+            |_type, count, name| VarHeader::new(_type, 0, count, name, "", "").var_type();
+        assert!(matches!(
+            var_type(DataVarType::Int, 1, "PlayerTrackSurface"),
+            VarType::Single(SingleVarType::TrackLocation)
+        ));
+        assert!(matches!(
+            var_type(DataVarType::Int, 64, "CarIdxTrackSurfaceMaterial"),
+            VarType::Array(SingleVarType::TrackSurface)
+        ));
+        assert!(matches!(
+            var_type(DataVarType::Int, 1, "PlayerCarPitSvStatus"),
+            VarType::Single(SingleVarType::PitStatus)
+        ));
+        assert!(matches!(
+            var_type(DataVarType::BitField, 1, "PlayerIncidents"),
+            VarType::Single(SingleVarType::IncidentFlags)
+        ));
+        assert!(matches!(
+            var_type(DataVarType::Bool, 64, "CarIdxP2P_Status"),
+            VarType::Array(SingleVarType::Bool)
+        ));
+    }
+}
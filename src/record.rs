@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{Serialize, Serializer};
+
+use crate::error::IrError;
+use crate::ir_data::IrData;
+use crate::mapped_file::full_value::{SingleVarType, VarType};
+use crate::mapped_file::var_header::VarHeader;
+use crate::types::*;
+
+/// Every variable of an `IrData` by name, created with `IrData::to_record`.  Serializes as a map in the order of the
+/// buffer row: numbers and bools as themselves, enums by the name of their variant, bitfields as a list of the names
+/// of the set flags and arrays as sequences.  Enum codes the crate does not know serialize as the code, and values
+/// that can not be read are `None`.
+/// ```
+/// use ir_telemetry::synthetic::SyntheticBuilder;
+///
+/// let telemetry = SyntheticBuilder::new(60)
+///     .var("Speed", "m/s", "GPS vehicle speed", |_| 42.5f32)
+///     .var("SessionState", "irsdk_SessionState", "Session state", |_| 4)
+///     .build();
+/// let (data, var_headers) = (telemetry.data_at(0), telemetry.var_headers());
+/// let record = data.to_record(&var_headers);
+/// assert_eq!(
+///     serde_json::to_string(&record).unwrap(),
+///     r#"{"Speed":42.5,"SessionState":"Racing"}"#
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Record<'a> {
+    data: &'a IrData,
+    variables: Vec<&'a VarHeader>,
+}
+
+impl<'a> Record<'a> {
+    pub(crate) fn new(data: &'a IrData, var_headers: &'a HashMap<String, VarHeader>) -> Self {
+        let mut variables = var_headers
+            .values()
+            .filter(|header| header.offset.max(0) as usize + header.size() <= data.data().len())
+            .collect::<Vec<_>>();
+        variables.sort_by_key(|header| (header.offset, header.name()));
+        Self { data, variables }
+    }
+
+    /// Number of variables in the record.
+    pub fn len(&self) -> usize {
+        self.variables.len()
+    }
+
+    /// Returns true if the record has no variables.
+    pub fn is_empty(&self) -> bool {
+        self.variables.is_empty()
+    }
+}
+
+impl Serialize for Record<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.variables.len()))?;
+        for variable in &self.variables {
+            map.serialize_entry(
+                variable.name(),
+                &Value {
+                    data: self.data,
+                    variable,
+                },
+            )?;
+        }
+        map.end()
+    }
+}
+
+/// A single variable of a record.
+struct Value<'a> {
+    data: &'a IrData,
+    variable: &'a VarHeader,
+}
+
+impl Value<'_> {
+    fn serialize_as<T, S>(&self, is_array: bool, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: TryFrom<IrValue> + Serialize,
+        <T as TryFrom<IrValue>>::Error: Into<IrError>,
+        S: Serializer,
+    {
+        if is_array {
+            self.data
+                .get::<Vec<T>>(Some(self.variable))
+                .serialize(serializer)
+        } else {
+            self.data
+                .get::<T>(Some(self.variable))
+                .serialize(serializer)
+        }
+    }
+
+    fn serialize_flags<T, S>(&self, is_array: bool, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: TryFrom<IrValue> + bitflags::Flags,
+        T::Bits: Serialize,
+        <T as TryFrom<IrValue>>::Error: Into<IrError>,
+        S: Serializer,
+    {
+        if is_array {
+            match self.data.get::<Vec<T>>(Some(self.variable)) {
+                Some(flags) => {
+                    let mut seq = serializer.serialize_seq(Some(flags.len()))?;
+                    for flags in flags {
+                        seq.serialize_element(&FlagNames(flags))?;
+                    }
+                    seq.end()
+                }
+                None => serializer.serialize_none(),
+            }
+        } else {
+            self.data
+                .get::<T>(Some(self.variable))
+                .map(FlagNames)
+                .serialize(serializer)
+        }
+    }
+}
+
+impl Serialize for Value<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (single, is_array) = match self.variable.var_type() {
+            VarType::Single(single) => (single, false),
+            VarType::Array(single) => (single, true),
+        };
+        match single {
+            SingleVarType::Char | SingleVarType::Int => {
+                self.serialize_as::<i32, S>(is_array, serializer)
+            }
+            SingleVarType::Bool => self.serialize_as::<bool, S>(is_array, serializer),
+            SingleVarType::Float => self.serialize_as::<f32, S>(is_array, serializer),
+            SingleVarType::Double => self.serialize_as::<f64, S>(is_array, serializer),
+            SingleVarType::TrackLocation => {
+                self.serialize_as::<TrackLocation, S>(is_array, serializer)
+            }
+            SingleVarType::TrackSurface => {
+                self.serialize_as::<TrackSurface, S>(is_array, serializer)
+            }
+            SingleVarType::SessionState => {
+                self.serialize_as::<SessionState, S>(is_array, serializer)
+            }
+            SingleVarType::CarLeftRight => {
+                self.serialize_as::<CarLeftRight, S>(is_array, serializer)
+            }
+            SingleVarType::PitStatus => self.serialize_as::<PitStatus, S>(is_array, serializer),
+            SingleVarType::PaceMode => self.serialize_as::<PaceMode, S>(is_array, serializer),
+            SingleVarType::TrackWetness => {
+                self.serialize_as::<TrackWetness, S>(is_array, serializer)
+            }
+            SingleVarType::EngineWarnings => {
+                self.serialize_flags::<EngineWarnings, S>(is_array, serializer)
+            }
+            SingleVarType::Flags => self.serialize_flags::<Flags, S>(is_array, serializer),
+            SingleVarType::CameraState => {
+                self.serialize_flags::<CameraState, S>(is_array, serializer)
+            }
+            SingleVarType::PitServiceFlags => {
+                self.serialize_flags::<PitServiceFlags, S>(is_array, serializer)
+            }
+            SingleVarType::PaceFlags => self.serialize_flags::<PaceFlags, S>(is_array, serializer),
+            SingleVarType::IncidentFlags => {
+                self.serialize_as::<IncidentFlags, S>(is_array, serializer)
+            }
+        }
+    }
+}
+
+/// Serializes bitflags as the names of the set flags, followed by the bits without a name as a number, if any.
+struct FlagNames<T>(T);
+
+impl<T: bitflags::Flags> Serialize for FlagNames<T>
+where
+    T::Bits: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(None)?;
+        let mut names = self.0.iter_names();
+        for (name, _) in names.by_ref() {
+            seq.serialize_element(name)?;
+        }
+        if !names.remaining().is_empty() {
+            seq.serialize_element(&names.remaining().bits())?;
+        }
+        seq.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::synthetic::SyntheticBuilder;
+    use crate::types::{EngineWarnings, Flags};
+
+    #[test]
+    fn test_record_serializes_every_variable() {
+        let telemetry = SyntheticBuilder::new(60)
+            .var("SessionTime", "s", "Seconds since session start", |_| {
+                1.5f64
+            })
+            .var("OnPitRoad", "", "Is the player car on pit road", |_| true)
+            .var(
+                "SessionState",
+                "irsdk_SessionState",
+                "Session state",
+                |_| 42,
+            )
+            .var("SessionFlags", "irsdk_Flags", "Session flags", |_| {
+                Flags::GREEN_FLAG | Flags::YELLOW_WAVING
+            })
+            .var(
+                "EngineWarnings",
+                "irsdk_EngineWarnings",
+                "Engine warnings",
+                |_| EngineWarnings::from_bits_retain(0x8001),
+            )
+            .array(
+                "CarIdxTrackSurface",
+                3,
+                "irsdk_TrkLoc",
+                "Track surface",
+                |_, i| i as i32 - 1,
+            )
+            .build();
+        let (data, var_headers) = (telemetry.data_at(0), telemetry.var_headers());
+        let record = data.to_record(&var_headers);
+        assert_eq!(record.len(), 6);
+        assert_eq!(
+            serde_json::to_value(&record).unwrap(),
+            json!({
+                "SessionTime": 1.5,
+                "OnPitRoad": true,
+                "SessionState": 42,
+                "SessionFlags": ["GREEN_FLAG", "YELLOW_WAVING"],
+                // The bits the crate has no name for are kept.
+                "EngineWarnings": ["WATER_TEMP", 0x8000],
+                "CarIdxTrackSurface": ["NotInWorld", "OffTrack", "InPitStall"],
+            })
+        );
+    }
+}
@@ -0,0 +1,525 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::channel::{channel, Backpressure, Receiver, Sender};
+use crate::client::{Client, DisconnectReason, UpdatePacket};
+use crate::diagnostics::Diagnostics;
+use crate::error::Result;
+use crate::ibt::file::IbtFile;
+use crate::ir_data::IrData;
+use crate::mapped_file::dumpfile::DumpFile;
+use crate::mapped_file::header::Header;
+use crate::mapped_file::var_header::VarHeader;
+use crate::source::TelemetrySource;
+
+/// A recording that can be played back with `Client::replay`.  Replayed data has the capture time read from the
+/// recording and no latency, as latency is not recorded.
+#[derive(Debug)]
+pub enum Recording {
+    /// An iRacing disk telemetry file.
+    Ibt(IbtFile),
+    /// A single snapshot of the memory mapped file.
+    Dump(DumpFile),
+}
+
+impl Recording {
+    /// Opens an .ibt file if the path has the `ibt` extension, otherwise a dump file.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let is_ibt = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("ibt"));
+        if is_ibt {
+            IbtFile::open(path).map(Recording::Ibt)
+        } else {
+            DumpFile::open(path).map(Recording::Dump)
+        }
+    }
+
+    /// Number of records in the recording.
+    pub fn len(&self) -> usize {
+        match self {
+            Recording::Ibt(ibt) => ibt.len(),
+            Recording::Dump(_) => 1,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The header of the recorded data.
+    pub fn header(&self) -> Result<Header> {
+        match self {
+            Recording::Ibt(ibt) => Ok(*ibt.header()),
+            Recording::Dump(dump) => dump.header(),
+        }
+    }
+
+    /// Ticks per second of the recorded data.
+    pub fn tick_rate(&self) -> i32 {
+        match self {
+            Recording::Ibt(ibt) => ibt.header().tick_rate(),
+            Recording::Dump(dump) => dump.header().map(|h| h.tick_rate()).unwrap_or_default(),
+        }
+    }
+
+    /// Reads the record at `index`, with the variable headers and session time attached.
+    pub fn record(&self, index: usize) -> Result<IrData> {
+        match self {
+            Recording::Ibt(ibt) => ibt.record(index),
+            Recording::Dump(dump) => {
+                let var_headers = self.var_headers()?;
+                let mut data = dump.get_new_data()?;
+                data.stamp_session_time(var_headers.get("SessionTime"));
+                Ok(data.with_var_headers(Arc::new(var_headers)))
+            }
+        }
+    }
+
+    /// The variable headers keyed by name.
+    pub fn var_headers(&self) -> Result<HashMap<String, VarHeader>> {
+        match self {
+            Recording::Ibt(ibt) => Ok(ibt.var_header_map()),
+            Recording::Dump(dump) => Ok(dump
+                .get_var_headers()?
+                .into_iter()
+                .map(|h| (h.name.clone(), h))
+                .collect()),
+        }
+    }
+
+    /// The yaml formatted session info string.
+    pub fn session_info(&self) -> Result<String> {
+        match self {
+            Recording::Ibt(ibt) => Ok(ibt.session_info().to_string()),
+            Recording::Dump(dump) => dump.session_info(),
+        }
+    }
+}
+
+/// The slowest playback speed, a thousandth of real time.
+const MIN_SPEED: f64 = 0.001;
+
+/// Clamps a playback speed to at least `MIN_SPEED`, or `None` if it is not a number.
+fn clamp_speed(speed: f64) -> Option<f64> {
+    (!speed.is_nan()).then(|| speed.max(MIN_SPEED))
+}
+
+#[derive(Debug)]
+struct ReplayState {
+    position: usize,
+    paused: bool,
+    steps: usize,
+    speed: f64,
+    stopped: bool,
+    // Incremented on every control change, so a pending record is re-timed.
+    generation: u64,
+}
+
+#[derive(Debug)]
+struct Shared {
+    state: Mutex<ReplayState>,
+    changed: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, ReplayState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn update(&self, f: impl FnOnce(&mut ReplayState)) {
+        let mut state = self.lock();
+        f(&mut state);
+        state.generation += 1;
+        self.changed.notify_all();
+    }
+}
+
+/// Controls a replay started with `Client::replay`.  Dropping the handle stops the replay and closes the channel.
+#[derive(Debug)]
+pub struct ReplayHandle {
+    shared: Arc<Shared>,
+    len: usize,
+}
+
+impl ReplayHandle {
+    /// Pauses playback.  Records can still be sent one at a time with `step`.
+    pub fn pause(&self) {
+        self.shared.update(|s| s.paused = true);
+    }
+
+    /// Resumes playback from the current position.
+    pub fn resume(&self) {
+        self.shared.update(|s| s.paused = false);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.shared.lock().paused
+    }
+
+    /// Moves to the record at `index`.  The next record sent will be this one.
+    pub fn seek(&self, index: usize) {
+        let index = index.min(self.len);
+        self.shared.update(|s| s.position = index);
+    }
+
+    /// Sends the next record while paused.
+    pub fn step(&self) {
+        self.shared.update(|s| {
+            if s.paused {
+                s.steps += 1
+            }
+        });
+    }
+
+    /// Changes the playback speed multiplier.  `1.0` is real time.  Speeds under a thousandth are clamped to it, and
+    /// a speed that is not a number keeps the current speed.
+    pub fn set_speed(&self, speed: f64) {
+        if let Some(speed) = clamp_speed(speed) {
+            self.shared.update(|s| s.speed = speed);
+        }
+    }
+
+    pub fn speed(&self) -> f64 {
+        self.shared.lock().speed
+    }
+
+    /// Index of the next record to be sent.
+    pub fn position(&self) -> usize {
+        self.shared.lock().position
+    }
+
+    /// Number of records in the recording.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Stops the replay.  The channel is closed once the replay thread exits.
+    pub fn stop(&self) {
+        self.shared.update(|s| s.stopped = true);
+    }
+}
+
+impl Drop for ReplayHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl Client<Recording> {
+    /// Plays back an .ibt or dump file over the same kind of channel as `Client::connect`, starting with the same
+    /// `Connected`, `VariableHeaders` and `SessionInfo` packets.
+    /// Records are sent at the pace they were recorded, using `SessionTime` when available and the header's
+    /// `tick_rate` otherwise, scaled by `speed`, which is clamped like `ReplayHandle::set_speed` and is real time if it
+    /// is not a number.  `UpdatePacket::Disconnected` is sent when the end of the
+    /// recording is reached, after which the replay waits for a `seek` or for the handle to be dropped.
+    /// ```no_run
+    /// use ir_telemetry::{Client, UpdatePacket};
+    ///
+    /// // Play back at 10 times the recorded speed
+    /// let (rx, handle) = Client::replay("session.ibt", 10.).unwrap();
+    /// for packet in rx {
+    ///     match packet {
+    ///         UpdatePacket::Data(data) => println!("Tick: {}", data.tick()),
+    ///         UpdatePacket::Disconnected { .. } => break,
+    ///         _ => (),
+    ///     }
+    /// }
+    /// handle.stop();
+    /// ```
+    pub fn replay<P: AsRef<Path>>(path: P, speed: f64) -> Result<(Receiver, ReplayHandle)> {
+        Self::replay_recording(Recording::open(path)?, speed)
+    }
+
+    /// Plays back an already opened recording.  See `Client::replay`.
+    pub fn replay_recording(recording: Recording, speed: f64) -> Result<(Receiver, ReplayHandle)> {
+        let header = recording.header()?;
+        let var_headers = recording.var_headers()?;
+        let session_info = recording.session_info()?;
+        let len = recording.len();
+        let shared = Arc::new(Shared {
+            state: Mutex::new(ReplayState {
+                position: 0,
+                paused: false,
+                steps: 0,
+                speed: clamp_speed(speed).unwrap_or(1.),
+                stopped: false,
+                generation: 0,
+            }),
+            changed: Condvar::new(),
+        });
+
+        // Playback never drops data, a slow receiver slows down the playback instead.
+        let (tx, rx) = channel(4, Backpressure::Block, Diagnostics::default());
+        let mut player = Player {
+            session_time: var_headers.get("SessionTime").cloned(),
+            recording,
+            shared: shared.clone(),
+            tx,
+        };
+        std::thread::spawn(move || {
+            // The same packets a client sends when it connects.
+            let started = player
+                .tx
+                .send(UpdatePacket::Connected {
+                    tick_rate: header.tick_rate(),
+                    header_version: header.version(),
+                })
+                .and_then(|_| player.tx.send(UpdatePacket::VariableHeaders(var_headers)));
+            if started.is_ok()
+                && player
+                    .tx
+                    .send(UpdatePacket::SessionInfo(session_info))
+                    .is_ok()
+            {
+                player.run();
+            }
+            log::debug!("Replay stopped");
+        });
+
+        Ok((rx, ReplayHandle { shared, len }))
+    }
+}
+
+struct Player {
+    recording: Recording,
+    session_time: Option<VarHeader>,
+    shared: Arc<Shared>,
+    tx: Sender,
+}
+
+impl Player {
+    /// Time of the record in seconds, relative to the start of the recording.
+    fn record_time(&self, data: &IrData) -> f64 {
+        match data.get::<f64>(self.session_time.as_ref()) {
+            Some(time) => time,
+            None => data.tick() as f64 / self.recording.tick_rate().max(1) as f64,
+        }
+    }
+
+    fn run(&mut self) {
+        // Wall clock instant and record time that pacing is measured from.
+        let mut anchor: Option<(Instant, f64)> = None;
+        let mut sent_end = false;
+        let mut state = self.shared.lock();
+        loop {
+            if state.stopped {
+                return;
+            }
+            let at_end = state.position >= self.recording.len();
+            if at_end && !sent_end {
+                drop(state);
+                if self
+                    .tx
+                    .send(UpdatePacket::Disconnected {
+                        reason: DisconnectReason::EndOfRecording,
+                    })
+                    .is_err()
+                {
+                    return;
+                }
+                sent_end = true;
+                state = self.shared.lock();
+                continue;
+            }
+            if at_end || (state.paused && state.steps == 0) {
+                anchor = None;
+                state = self
+                    .shared
+                    .changed
+                    .wait(state)
+                    .unwrap_or_else(|e| e.into_inner());
+                continue;
+            }
+            sent_end = false;
+
+            let position = state.position;
+            let generation = state.generation;
+            let stepping = state.paused;
+            let speed = state.speed;
+            drop(state);
+
+            let data = match self.recording.record(position) {
+                Ok(data) => data,
+                Err(e) => {
+                    log::error!("Error reading record {}: {}", position, e);
+                    return;
+                }
+            };
+            let time = self.record_time(&data);
+
+            if !stepping {
+                let (start, start_time) = match anchor {
+                    Some((start, start_time)) if time >= start_time => (start, start_time),
+                    _ => (Instant::now(), time),
+                };
+                anchor = Some((start, start_time));
+                // A record too far ahead to be due only waits for the controls to change.
+                let due = Duration::try_from_secs_f64((time - start_time) / speed)
+                    .ok()
+                    .and_then(|offset| start.checked_add(offset));
+
+                // Wait until the record is due, unless the controls change in the mean time.
+                state = self.shared.lock();
+                while state.generation == generation && !state.stopped {
+                    let now = Instant::now();
+                    state = match due {
+                        Some(due) if now >= due => break,
+                        Some(due) => {
+                            self.shared
+                                .changed
+                                .wait_timeout(state, due - now)
+                                .unwrap_or_else(|e| e.into_inner())
+                                .0
+                        }
+                        None => self
+                            .shared
+                            .changed
+                            .wait(state)
+                            .unwrap_or_else(|e| e.into_inner()),
+                    };
+                }
+                if state.generation != generation || state.stopped {
+                    anchor = None;
+                    continue;
+                }
+                drop(state);
+            } else {
+                anchor = None;
+            }
+
+            if self.tx.send(UpdatePacket::Data(data)).is_err() {
+                return;
+            }
+
+            state = self.shared.lock();
+            // Only advance if the position was not changed while sending.
+            if state.position == position {
+                state.position += 1;
+            }
+            if stepping && state.paused {
+                state.steps = state.steps.saturating_sub(1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ibt::writer::IbtWriter;
+    use crate::mapped_file::values::DataVarType;
+
+    /// Writes a recording with one record per session time.
+    fn write_recording(name: &str, times: &[f64]) -> std::path::PathBuf {
+        let path = crate::utils::temp_path(name);
+        let headers = [VarHeader::new(
+            DataVarType::Double,
+            0,
+            1,
+            "SessionTime",
+            "Seconds since session start",
+            "s",
+        )];
+        let mut writer = IbtWriter::create(&path, headers.iter(), "---\n...\n").unwrap();
+        for (tick, time) in times.iter().enumerate() {
+            let data = IrData::new(
+                time.to_le_bytes().to_vec(),
+                Header::connected(60),
+                tick as i32,
+            );
+            writer.write(&data).unwrap();
+        }
+        path
+    }
+
+    /// Checks the packets sent before the records, like those of a client connecting.
+    fn expect_connected(rx: &Receiver) {
+        assert!(matches!(
+            rx.recv().unwrap(),
+            UpdatePacket::Connected { tick_rate: 60, .. }
+        ));
+        assert!(matches!(
+            rx.recv().unwrap(),
+            UpdatePacket::VariableHeaders(_)
+        ));
+        assert!(matches!(rx.recv().unwrap(), UpdatePacket::SessionInfo(_)));
+    }
+
+    fn next_tick(rx: &Receiver) -> Option<i32> {
+        match rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+            UpdatePacket::Data(data) => Some(data.tick()),
+            UpdatePacket::Disconnected {
+                reason: DisconnectReason::EndOfRecording,
+            } => None,
+            packet => panic!("Unexpected packet: {:?}", packet),
+        }
+    }
+
+    #[test]
+    fn test_replay_sends_all_records() {
+        let path = write_recording("replay_all.ibt", &[0., 0.1, 0.2]);
+        let (rx, handle) = Client::replay(&path, 100.).unwrap();
+        assert_eq!(handle.len(), 3);
+        expect_connected(&rx);
+
+        let ticks = std::iter::from_fn(|| next_tick(&rx)).collect::<Vec<_>>();
+        assert_eq!(ticks, vec![0, 1, 2]);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_replay_controls() {
+        // Records are far enough apart that only the first is sent without stepping.
+        let path = write_recording("replay_controls.ibt", &[0., 100., 200., 300.]);
+        let (rx, handle) = Client::replay(&path, 1.).unwrap();
+        expect_connected(&rx);
+        assert_eq!(next_tick(&rx), Some(0));
+
+        handle.pause();
+        handle.seek(2);
+        handle.step();
+        assert_eq!(next_tick(&rx), Some(2));
+
+        handle.step();
+        assert_eq!(next_tick(&rx), Some(3));
+        handle.step();
+        assert_eq!(next_tick(&rx), None);
+        assert_eq!(handle.position(), 4);
+
+        handle.seek(1);
+        handle.set_speed(1000.);
+        handle.resume();
+        assert_eq!(next_tick(&rx), Some(1));
+        handle.stop();
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_tiny_speeds_are_clamped() {
+        let path = write_recording("replay_tiny_speed.ibt", &[0., 1., 2.]);
+        let (rx, handle) = Client::replay(&path, f64::NAN).unwrap();
+        assert_eq!(handle.speed(), 1.);
+        expect_connected(&rx);
+        assert_eq!(next_tick(&rx), Some(0));
+
+        // Used to overflow the wait for the next record and end the replay thread.
+        handle.set_speed(1e-300);
+        assert_eq!(handle.speed(), MIN_SPEED);
+        handle.set_speed(f64::NAN);
+        assert_eq!(handle.speed(), MIN_SPEED);
+        handle.set_speed(1000.);
+        assert_eq!(next_tick(&rx), Some(1));
+        assert_eq!(next_tick(&rx), Some(2));
+        assert_eq!(next_tick(&rx), None);
+        handle.stop();
+        std::fs::remove_file(path).unwrap();
+    }
+}
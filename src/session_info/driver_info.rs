@@ -11,7 +11,7 @@ pub struct DriverInfo {
     pub driver_head_pos_y: f32,
     pub driver_head_pos_z: f32,
 
-    #[serde(rename = "DriverCarSLFirstRPM")]
+    #[serde(rename = "DriverCarIdleRPM")]
     pub driver_car_idle_rpm: f32,
     pub driver_car_red_line: f32,
     pub driver_car_fuel_kg_per_ltr: f32,
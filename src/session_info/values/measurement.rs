@@ -1,17 +1,38 @@
 use serde::{Deserialize, Deserializer, Serialize};
 
+use crate::units::{Quantity, Unit};
+
 pub type MeasurementF32 = Measurement<f32>;
 pub type MeasurementI32 = Measurement<i32>;
 
 /// Used when session data is a string with a value and unit of measurement.
 /// Use the feature `value_only_measurement` to only serialize the value.  This is useful
-/// in that numbers will be serialized as numbers and not strings.
+/// in that numbers will be serialized as numbers and not strings.  The value still deserializes, but the unit is lost.
 #[derive(Debug, Clone, Default)]
 pub struct Measurement<V> {
     pub value: V,
     pub unit: Option<String>,
 }
 
+impl<V: Copy + Into<f64>> Measurement<V> {
+    /// The parsed unit, if it is one that converts, like `km` or `C`.
+    pub fn unit_type(&self) -> Option<Unit> {
+        self.unit.as_deref().and_then(Unit::parse)
+    }
+
+    /// The value with its parsed unit.
+    pub fn quantity(&self) -> Option<Quantity> {
+        self.unit_type()
+            .map(|unit| Quantity::new(self.value.into(), unit))
+    }
+
+    /// Converts the value to `unit`.  Returns `None` if the measurement has no unit or one that does not convert
+    /// to `unit`.
+    pub fn to(&self, unit: Unit) -> Option<Quantity> {
+        self.quantity()?.to(unit)
+    }
+}
+
 impl<'de, T> Deserialize<'de> for Measurement<T>
 where
     T: std::str::FromStr + Default,
@@ -47,7 +68,7 @@ where
             #[cfg(not(feature = "value_only_measurement"))]
             Some(unit) => serializer.serialize_str(&format!("{} {}", self.value, unit)),
             #[cfg(feature = "value_only_measurement")]
-            Some(unit) => self.value.serialize(serializer),
+            Some(_) => self.value.serialize(serializer),
             None => serializer.serialize_str(&format!("{}", self.value)),
         }
     }
@@ -57,6 +78,7 @@ impl<T> std::fmt::Display for Measurement<T>
 where
     T: std::fmt::Display,
 {
+    #[allow(clippy::unnecessary_unwrap)]
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if self.unit.is_none() {
             write!(f, "{}", self.value)
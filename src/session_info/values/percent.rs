@@ -2,7 +2,7 @@ use serde::{Deserialize, Deserializer, Serialize};
 
 /// Used when session data is a string with a percent value.
 /// Use the feature `value_only_measurement` to only serialize the value.  This is useful
-/// in that numbers will be serialized as numbers and not strings.
+/// in that numbers will be serialized as numbers and not strings.  Values without the `%` deserialize too.
 #[derive(Debug, Clone, Default)]
 pub struct Percent(pub f32);
 
@@ -12,9 +12,9 @@ impl<'de> Deserialize<'de> for Percent {
         D: Deserializer<'de>,
     {
         let str = String::deserialize(deserializer)?;
-        let value = str
-            .split_once('%')
-            .and_then(|(v, _)| {
+        // Without a `%`, the value was serialized with `value_only_measurement`.
+        let value = Some(str.split_once('%').map_or(str.as_str(), |(v, _)| v))
+            .and_then(|v| {
                 let v = v.trim();
                 v.parse::<f32>()
                     .map_err(|_| (v.to_owned() + ".").parse::<f32>())
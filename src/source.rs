@@ -0,0 +1,303 @@
+use std::time::Duration;
+
+use crate::data_view::DataView;
+use crate::diagnostics::Diagnostics;
+use crate::error::{IrError, Result};
+use crate::ir_data::IrData;
+use crate::mapped_file::header::Header;
+use crate::mapped_file::var_header::VarHeader;
+
+/// A provider of iRacing telemetry.
+/// The live memory mapped file (`FileMap`, Windows only) is one implementation.  Any other backend, like a
+/// recording or a network feed, can implement this trait and be driven by `Client::connect_with` to produce the
+/// same `UpdatePacket` stream.
+pub trait TelemetrySource {
+    /// Returns a copy of the current header.
+    fn header(&self) -> Result<Header>;
+
+    /// Returns true if the sim reports an active session.
+    fn is_connected(&self) -> bool {
+        self.header().map(|h| h.is_connected()).unwrap_or(false)
+    }
+
+    /// Blocks until new data is available or the timeout expires.  Returns true if the source signaled new data, and
+    /// false if the timeout expired or the source can not signal, which leaves the latency of the data unknown.
+    fn wait_for_update(&self, timeout: Duration) -> bool;
+
+    /// Copies the most recent telemetry buffer.  The returned data is owned by the caller.
+    fn get_new_data(&self) -> Result<IrData>;
+
+    /// Calls `f` with a borrowed view of the most recent buffer, for consumers that only read a few variables.  The
+    /// tick count is checked again after `f` returns, and if the sim overwrote the buffer in the meantime `f` is
+    /// called again on the newer buffer, so `f` should only read.  By default the buffer is copied with
+    /// `get_new_data`; the memory mapped file and dump files lend their buffer without copying it.
+    /// A `ClientHandle` has no `with_latest`: the client opens and reads its source on its own thread, so a view of
+    /// the buffer can not be lent to the receiving thread.  Open a source of your own to read it this way.
+    fn with_latest<R, F>(&self, mut f: F) -> Result<R>
+    where
+        F: FnMut(&DataView<'_>) -> R,
+        Self: Sized,
+    {
+        let data = self.get_new_data()?;
+        Ok(f(&DataView::new(data.data(), data.header(), data.tick())))
+    }
+
+    /// Copies every buffer with a tick count after `after_tick`, oldest first.  The sim rotates through a few
+    /// buffers, so ticks older than those are gone.  By default only the most recent buffer is returned.
+    fn get_buffered_data(&self, after_tick: i32) -> Result<Vec<IrData>> {
+        let data = self.get_new_data()?;
+        Ok(if data.tick() > after_tick {
+            vec![data]
+        } else {
+            Vec::new()
+        })
+    }
+
+    /// Returns the variable headers describing the layout of the telemetry buffer.
+    fn get_var_headers(&self) -> Result<Vec<VarHeader>>;
+
+    /// Returns the yaml formatted session info string.
+    fn session_info(&self) -> Result<String>;
+}
+
+/// Number of times a torn buffer copy is attempted before the update is skipped.
+const MAX_READ_ATTEMPTS: usize = 3;
+
+/// Copies the most recent telemetry buffer with `copy(offset, len)`.  The sim keeps writing to the buffers while they
+/// are copied, so the tick count of the buffer is checked again after the copy and the copy is retried if it changed.
+pub(crate) fn read_latest_buffer<H, C>(
+    read_header: H,
+    copy: C,
+    diagnostics: &Diagnostics,
+) -> Result<IrData>
+where
+    H: Fn() -> Result<Header>,
+    C: Fn(usize, usize) -> Result<Vec<u8>>,
+{
+    for _ in 0..MAX_READ_ATTEMPTS {
+        let header = read_header()?;
+        let index = header.most_recent_buffer_index();
+        let buffer = header.var_buf[index];
+        let data = copy(buffer.offset as usize, header.buf_len as usize)?;
+        if read_header()?.var_buf[index].tick_count == buffer.tick_count {
+            return Ok(IrData::new(data, header, buffer.tick_count));
+        }
+        diagnostics.record_torn_read();
+    }
+    diagnostics.record_dropped_read();
+    Err(IrError::TornRead {
+        attempts: MAX_READ_ATTEMPTS,
+    })
+}
+
+/// Calls `f` with a view of the most recent telemetry buffer, borrowed with `borrow(offset, len)`.  Like
+/// `read_latest_buffer`, the tick count of the buffer is checked again after `f` and the result is thrown away if it
+/// changed.
+pub(crate) fn view_latest_buffer<'a, H, B, F, R>(
+    read_header: H,
+    borrow: B,
+    mut f: F,
+    diagnostics: &Diagnostics,
+) -> Result<R>
+where
+    H: Fn() -> Result<Header>,
+    B: Fn(usize, usize) -> Result<&'a [u8]>,
+    F: FnMut(&DataView<'_>) -> R,
+{
+    for _ in 0..MAX_READ_ATTEMPTS {
+        let header = read_header()?;
+        let index = header.most_recent_buffer_index();
+        let buffer = header.var_buf[index];
+        let row = borrow(buffer.offset as usize, header.buf_len as usize)?;
+        let result = f(&DataView::new(row, &header, buffer.tick_count));
+        if read_header()?.var_buf[index].tick_count == buffer.tick_count {
+            return Ok(result);
+        }
+        diagnostics.record_torn_read();
+    }
+    diagnostics.record_dropped_read();
+    Err(IrError::TornRead {
+        attempts: MAX_READ_ATTEMPTS,
+    })
+}
+
+/// Copies all buffers with a tick count after `after_tick` with `copy(offset, len)`, oldest first.  A buffer the sim
+/// overwrote while it was copied is left out, as it now holds a newer tick that is read on the next call.
+pub(crate) fn read_buffers_after<H, C>(
+    read_header: H,
+    copy: C,
+    after_tick: i32,
+    diagnostics: &Diagnostics,
+) -> Result<Vec<IrData>>
+where
+    H: Fn() -> Result<Header>,
+    C: Fn(usize, usize) -> Result<Vec<u8>>,
+{
+    let header = read_header()?;
+    let mut buffered = Vec::new();
+    for index in header.buffer_indices_by_tick() {
+        let buffer = header.var_buf[index];
+        if buffer.tick_count <= after_tick {
+            continue;
+        }
+        let data = copy(buffer.offset as usize, header.buf_len as usize)?;
+        if read_header()?.var_buf[index].tick_count == buffer.tick_count {
+            buffered.push(IrData::new(data, header, buffer.tick_count));
+        } else {
+            diagnostics.record_torn_read();
+        }
+    }
+    Ok(buffered)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+
+    use super::*;
+
+    const ROW_OFFSET: usize = 112;
+
+    /// A memory map with one 4 byte row.  Each copy of the row is torn `tears` times: the sim writes the next tick
+    /// while the row is being copied.
+    struct TearingMap {
+        bytes: RefCell<Vec<u8>>,
+        tears: Cell<usize>,
+        diagnostics: Diagnostics,
+    }
+
+    impl TearingMap {
+        fn new(tears: usize) -> Self {
+            let mut header = Header::connected(60);
+            header.num_buf = 1;
+            header.buf_len = 4;
+            header.var_buf[0].offset = ROW_OFFSET as i32;
+            let mut bytes = header.to_bytes();
+            bytes.extend_from_slice(&0i32.to_le_bytes());
+            Self {
+                bytes: RefCell::new(bytes),
+                tears: Cell::new(tears),
+                diagnostics: Diagnostics::new(),
+            }
+        }
+
+        fn header(&self) -> Result<Header> {
+            Header::parse(&self.bytes.borrow())
+        }
+
+        fn copy(&self, offset: usize, len: usize) -> Result<Vec<u8>> {
+            let data = self.bytes.borrow()[offset..offset + len].to_vec();
+            if self.tears.get() > 0 {
+                self.tears.set(self.tears.get() - 1);
+                // The sim moves on to the next tick and writes the row with the tick as value.
+                let mut header = self.header()?;
+                header.var_buf[0].tick_count += 1;
+                let tick = header.var_buf[0].tick_count;
+                let mut bytes = self.bytes.borrow_mut();
+                bytes[..ROW_OFFSET].copy_from_slice(&header.to_bytes());
+                bytes[ROW_OFFSET..ROW_OFFSET + 4].copy_from_slice(&tick.to_le_bytes());
+            }
+            Ok(data)
+        }
+
+        fn read(&self) -> Result<IrData> {
+            read_latest_buffer(
+                || self.header(),
+                |offset, len| self.copy(offset, len),
+                &self.diagnostics,
+            )
+        }
+    }
+
+    #[test]
+    fn test_torn_reads_are_retried() {
+        let map = TearingMap::new(2);
+        let data = map.read().unwrap();
+        assert_eq!(data.tick(), 2);
+        assert_eq!(data.data(), &2i32.to_le_bytes());
+        assert_eq!(map.diagnostics.torn_reads(), 2);
+        assert_eq!(map.diagnostics.dropped_reads(), 0);
+    }
+
+    #[test]
+    fn test_update_is_dropped_when_every_read_is_torn() {
+        let map = TearingMap::new(usize::MAX);
+        assert!(matches!(
+            map.read(),
+            Err(IrError::TornRead {
+                attempts: MAX_READ_ATTEMPTS
+            })
+        ));
+        assert_eq!(map.diagnostics.torn_reads(), MAX_READ_ATTEMPTS as u64);
+        assert_eq!(map.diagnostics.dropped_reads(), 1);
+    }
+
+    #[test]
+    fn test_overwritten_views_are_read_again() {
+        let mut header = Header::connected(60);
+        header.num_buf = 1;
+        header.buf_len = 4;
+        header.var_buf[0].offset = 0;
+        let row = 7i32.to_le_bytes();
+        // The sim writes a new tick during each of the first two views.
+        let reads = Cell::new(0);
+        let read_header = || {
+            let mut header = header;
+            header.var_buf[0].tick_count = reads.get().min(3);
+            reads.set(reads.get() + 1);
+            Ok(header)
+        };
+
+        let diagnostics = Diagnostics::new();
+        let mut views = Vec::new();
+        let tick = view_latest_buffer(
+            read_header,
+            |offset, len| Ok(&row[offset..offset + len]),
+            |view| {
+                views.push(view.data().to_vec());
+                view.tick()
+            },
+            &diagnostics,
+        )
+        .unwrap();
+        assert_eq!(tick, 3);
+        assert_eq!(views.len(), 3);
+        assert_eq!(diagnostics.torn_reads(), 2);
+    }
+
+    #[test]
+    fn test_buffers_are_read_in_tick_order() {
+        let mut header = Header::connected(60);
+        header.num_buf = 3;
+        header.buf_len = 4;
+        let mut bytes = Vec::new();
+        for (i, tick) in [5, 3, 4].into_iter().enumerate() {
+            header.var_buf[i].tick_count = tick;
+            header.var_buf[i].offset = (ROW_OFFSET + i * 4) as i32;
+            bytes.extend_from_slice(&tick.to_le_bytes());
+        }
+        bytes.splice(0..0, header.to_bytes());
+
+        let read = |after_tick| {
+            read_buffers_after(
+                || Header::parse(&bytes),
+                |offset, len| Ok(bytes[offset..offset + len].to_vec()),
+                after_tick,
+                &Diagnostics::default(),
+            )
+            .unwrap()
+            .iter()
+            .map(|data| (data.tick(), data.data().to_vec()))
+            .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            read(3),
+            vec![
+                (4, 4i32.to_le_bytes().to_vec()),
+                (5, 5i32.to_le_bytes().to_vec())
+            ]
+        );
+        assert_eq!(read(i32::MIN).len(), 3);
+        assert!(read(5).is_empty());
+    }
+}
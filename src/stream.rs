@@ -0,0 +1,138 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+
+use crate::builder::ClientBuilder;
+use crate::client::{Client, ClientHandle, UpdatePacket};
+use crate::error::Result;
+#[cfg(windows)]
+use crate::mapped_file::memfile::FileMap;
+use crate::source::TelemetrySource;
+
+/// A running client that yields updates as a `Stream`.  Requires the `async` feature.
+/// The source is still read on the client thread, which waits on the data valid event of the sim, so polling the
+/// stream never blocks the executor.  The event is not awaited on the executor itself: sources like the memory mapped
+/// file are not `Send`, and waiting on a Windows event without a thread needs a runtime specific wait registration,
+/// so every source is driven the same way and the stream is woken when the client queues a packet.
+/// Dropping the stream stops the client and releases the source.
+/// ```
+/// use std::future::poll_fn;
+/// use std::pin::Pin;
+/// use futures_core::Stream;
+/// use ir_telemetry::synthetic::SyntheticBuilder;
+/// use ir_telemetry::{Client, UpdatePacket};
+///
+/// let telemetry = SyntheticBuilder::new(60).session_clock().build();
+/// let mut stream = Client::connect_with_async(60., move || Ok(telemetry.source()));
+/// futures_executor::block_on(async {
+///     while let Some(packet) = poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await {
+///         if let UpdatePacket::Data(data) = packet {
+///             println!("Tick: {}", data.tick());
+///             break;
+///         }
+///     }
+/// });
+/// ```
+#[derive(Debug)]
+pub struct ClientStream {
+    handle: ClientHandle,
+}
+
+impl ClientStream {
+    /// Asks the client to stop.  The stream ends after the packets already queued.
+    pub fn stop(&self) {
+        self.handle.stop();
+    }
+
+    /// Returns true until the client thread has finished.
+    pub fn is_running(&self) -> bool {
+        self.handle.is_running()
+    }
+
+    /// The blocking handle of the client.
+    pub fn into_handle(self) -> ClientHandle {
+        self.handle
+    }
+}
+
+impl From<ClientHandle> for ClientStream {
+    fn from(handle: ClientHandle) -> Self {
+        Self { handle }
+    }
+}
+
+impl Stream for ClientStream {
+    type Item = UpdatePacket;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.handle.receiver().poll_recv(cx)
+    }
+}
+
+#[cfg(windows)]
+impl Client<FileMap> {
+    /// Like `Client::connect`, with the updates as a `Stream`.
+    pub fn connect_async(update_rate: f32) -> ClientStream {
+        Self::connect(update_rate).into()
+    }
+}
+
+impl<S: TelemetrySource + 'static> Client<S> {
+    /// Like `Client::connect_with`, with the updates as a `Stream`.
+    pub fn connect_with_async<F>(update_rate: f32, open: F) -> ClientStream
+    where
+        F: FnMut() -> Result<S> + Send + 'static,
+    {
+        Self::connect_with(update_rate, open).into()
+    }
+}
+
+impl<S: TelemetrySource + 'static> ClientBuilder<S> {
+    /// Starts the client thread, with the updates as a `Stream`.
+    pub fn connect_async(self) -> ClientStream {
+        self.connect().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::poll_fn;
+
+    use super::*;
+    use crate::synthetic::SyntheticBuilder;
+
+    #[test]
+    fn test_stream_yields_packets() {
+        let telemetry = SyntheticBuilder::new(60).session_clock().build();
+        let mut stream = Client::connect_with_async(60., move || Ok(telemetry.source()));
+        let mut next = move || {
+            let stream = &mut stream;
+            futures_executor::block_on(poll_fn(|cx| Pin::new(&mut *stream).poll_next(cx)))
+        };
+
+        assert!(matches!(next(), Some(UpdatePacket::Connected { .. })));
+        assert!(matches!(next(), Some(UpdatePacket::VariableHeaders(_))));
+        assert!(matches!(next(), Some(UpdatePacket::SessionInfo(_))));
+        assert!(matches!(next(), Some(UpdatePacket::Data(_))));
+    }
+
+    #[test]
+    fn test_stream_ends_after_stop() {
+        let telemetry = SyntheticBuilder::new(60).session_clock().build();
+        let mut stream = Client::connect_with_async(60., move || Ok(telemetry.source()));
+        stream.stop();
+        let remaining = futures_executor::block_on(async {
+            let mut count = 0;
+            while poll_fn(|cx| Pin::new(&mut stream).poll_next(cx))
+                .await
+                .is_some()
+            {
+                count += 1;
+            }
+            count
+        });
+        assert!(remaining <= 4);
+        assert!(stream.into_handle().join().is_ok());
+    }
+}
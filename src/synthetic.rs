@@ -0,0 +1,505 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::client::UpdatePacket;
+use crate::error::Result;
+use crate::ir_data::IrData;
+use crate::mapped_file::header::Header;
+use crate::mapped_file::values::DataVarType;
+use crate::mapped_file::var_header::VarHeader;
+use crate::session_info::session::Session;
+use crate::source::TelemetrySource;
+use crate::types::*;
+
+/// A value that can be written to a synthetic telemetry buffer.
+pub trait SyntheticValue {
+    /// The type of the variable in the buffer.
+    const VAR_TYPE: DataVarType;
+
+    /// Writes the value to `buffer`, which is `VAR_TYPE.amount_of_bytes()` long.
+    fn write_to(&self, buffer: &mut [u8]);
+}
+
+impl SyntheticValue for u8 {
+    const VAR_TYPE: DataVarType = DataVarType::Char;
+
+    fn write_to(&self, buffer: &mut [u8]) {
+        buffer[0] = *self;
+    }
+}
+
+impl SyntheticValue for bool {
+    const VAR_TYPE: DataVarType = DataVarType::Bool;
+
+    fn write_to(&self, buffer: &mut [u8]) {
+        buffer[0] = *self as u8;
+    }
+}
+
+impl SyntheticValue for i32 {
+    const VAR_TYPE: DataVarType = DataVarType::Int;
+
+    fn write_to(&self, buffer: &mut [u8]) {
+        buffer.copy_from_slice(&self.to_le_bytes());
+    }
+}
+
+impl SyntheticValue for f32 {
+    const VAR_TYPE: DataVarType = DataVarType::Float;
+
+    fn write_to(&self, buffer: &mut [u8]) {
+        buffer.copy_from_slice(&self.to_le_bytes());
+    }
+}
+
+impl SyntheticValue for f64 {
+    const VAR_TYPE: DataVarType = DataVarType::Double;
+
+    fn write_to(&self, buffer: &mut [u8]) {
+        buffer.copy_from_slice(&self.to_le_bytes());
+    }
+}
+
+macro_rules! impl_synthetic_value_enum {
+    ($type:ty) => {
+        impl SyntheticValue for $type {
+            const VAR_TYPE: DataVarType = DataVarType::Int;
+
+            fn write_to(&self, buffer: &mut [u8]) {
+                buffer.copy_from_slice(&self.code().to_le_bytes());
+            }
+        }
+    };
+}
+
+impl_synthetic_value_enum!(TrackLocation);
+impl_synthetic_value_enum!(TrackSurface);
+impl_synthetic_value_enum!(SessionState);
+impl_synthetic_value_enum!(CarLeftRight);
+impl_synthetic_value_enum!(PitStatus);
+impl_synthetic_value_enum!(PaceMode);
+impl_synthetic_value_enum!(TrackWetness);
+
+macro_rules! impl_synthetic_value_bitflags {
+    ($type:ty) => {
+        impl SyntheticValue for $type {
+            const VAR_TYPE: DataVarType = DataVarType::BitField;
+
+            fn write_to(&self, buffer: &mut [u8]) {
+                buffer.copy_from_slice(&self.bits().to_le_bytes());
+            }
+        }
+    };
+}
+
+impl_synthetic_value_bitflags!(EngineWarnings);
+impl_synthetic_value_bitflags!(Flags);
+impl_synthetic_value_bitflags!(CameraState);
+impl_synthetic_value_bitflags!(PitServiceFlags);
+impl_synthetic_value_bitflags!(PaceFlags);
+
+impl SyntheticValue for IncidentFlags {
+    const VAR_TYPE: DataVarType = DataVarType::BitField;
+
+    fn write_to(&self, buffer: &mut [u8]) {
+        buffer.copy_from_slice(&self.code().to_le_bytes());
+    }
+}
+
+/// Writes all entries of a variable for a tick.
+type Script = Box<dyn Fn(i32, &mut [u8]) + Send + Sync>;
+
+/// Builds a `SyntheticTelemetry`: a set of variables with scripted values per tick and a session info string.
+/// Useful for testing consumers of this crate without a running sim.
+/// ```
+/// use ir_telemetry::synthetic::SyntheticBuilder;
+/// use ir_telemetry::Session;
+///
+/// let telemetry = SyntheticBuilder::new(60)
+///     .session(&Session::default())
+///     .session_clock()
+///     // One lap every 90 seconds
+///     .circulating(60 * 90)
+///     .var("Speed", "m/s", "GPS vehicle speed", |tick| 40. + (tick as f32 / 60.).sin() * 10.)
+///     .array("CarIdxPosition", 64, "", "Cars position in race by car index", |_, car| car as i32)
+///     .build();
+///
+/// let var_headers = telemetry.var_headers();
+/// let data = telemetry.data_at(120);
+/// assert_eq!(data.get::<f64>(var_headers.get("SessionTime")), Some(2.));
+/// ```
+pub struct SyntheticBuilder {
+    tick_rate: i32,
+    vars: Vec<(VarHeader, Script)>,
+    session_info: String,
+}
+
+impl SyntheticBuilder {
+    /// Starts a telemetry world updated `tick_rate` times per second.
+    pub fn new(tick_rate: i32) -> Self {
+        Self {
+            tick_rate,
+            vars: Vec::new(),
+            session_info: "---\n...\n".to_string(),
+        }
+    }
+
+    /// Adds a variable whose value is computed from the tick.  A variable with the same name is replaced.
+    pub fn var<T, F>(self, name: &str, unit: &str, desc: &str, script: F) -> Self
+    where
+        T: SyntheticValue,
+        F: Fn(i32) -> T + Send + Sync + 'static,
+    {
+        self.array(name, 1, unit, desc, move |tick, _| script(tick))
+    }
+
+    /// Adds an array variable with `count` entries.  Each entry is computed from the tick and its index.
+    /// A variable with the same name is replaced.
+    pub fn array<T, F>(
+        mut self,
+        name: &str,
+        count: usize,
+        unit: &str,
+        desc: &str,
+        script: F,
+    ) -> Self
+    where
+        T: SyntheticValue,
+        F: Fn(i32, usize) -> T + Send + Sync + 'static,
+    {
+        let size = T::VAR_TYPE.amount_of_bytes();
+        let write: Script = Box::new(move |tick, buffer| {
+            for (index, entry) in buffer.chunks_exact_mut(size).enumerate() {
+                script(tick, index).write_to(entry);
+            }
+        });
+        let header = VarHeader::new(T::VAR_TYPE, 0, count as i32, name, desc, unit);
+        self.vars.retain(|(h, _)| h.name != name);
+        self.vars.push((header, write));
+        self
+    }
+
+    /// Adds `SessionTime` and `SessionTick`, derived from the tick and tick rate.
+    pub fn session_clock(self) -> Self {
+        let tick_rate = self.tick_rate.max(1) as f64;
+        self.var(
+            "SessionTime",
+            "s",
+            "Seconds since session start",
+            move |tick| tick as f64 / tick_rate,
+        )
+        .var("SessionTick", "", "Current update number", |tick| tick)
+    }
+
+    /// Adds `Lap` and `LapDistPct` for a car completing a lap every `ticks_per_lap` ticks, starting on lap 1.
+    pub fn circulating(self, ticks_per_lap: i32) -> Self {
+        let ticks_per_lap = ticks_per_lap.max(1);
+        self.var("Lap", "", "Laps started count", move |tick| {
+            1 + tick / ticks_per_lap
+        })
+        .var(
+            "LapDistPct",
+            "%",
+            "Percentage distance around lap",
+            move |tick| (tick % ticks_per_lap) as f32 / ticks_per_lap as f32,
+        )
+    }
+
+    /// Uses `session` as the session info.  With the `value_only_measurement` feature, the units of the measurements
+    /// are not serialized, so they are `None` when the session info is parsed back.
+    pub fn session(mut self, session: &Session) -> Self {
+        // Session only contains strings, numbers and maps, which always serialize.
+        self.session_info = serde_yaml::to_string(session).expect("Session serializes to yaml");
+        self
+    }
+
+    /// Uses a yaml formatted string as the session info.
+    pub fn session_info<S: Into<String>>(mut self, session_info: S) -> Self {
+        self.session_info = session_info.into();
+        self
+    }
+
+    /// Lays out the variables in a buffer row, in the order they were added.
+    pub fn build(self) -> SyntheticTelemetry {
+        let mut var_headers = Vec::with_capacity(self.vars.len());
+        let mut scripts = Vec::with_capacity(self.vars.len());
+        let mut row_len = 0;
+        for (mut header, script) in self.vars {
+            header.offset = row_len as i32;
+            row_len += header.size();
+            var_headers.push(header);
+            scripts.push(script);
+        }
+
+        let mut header = Header::connected(self.tick_rate);
+        header.num_vars = var_headers.len() as i32;
+        header.num_buf = 1;
+        header.buf_len = row_len as i32;
+        header.session_info_len = self.session_info.len() as i32;
+
+        SyntheticTelemetry {
+            inner: Arc::new(SyntheticInner {
+                header,
+                var_header_map: Arc::new(
+                    var_headers
+                        .iter()
+                        .map(|h| (h.name.clone(), h.clone()))
+                        .collect(),
+                ),
+                var_headers,
+                scripts,
+                session_info: self.session_info,
+            }),
+        }
+    }
+}
+
+struct SyntheticInner {
+    header: Header,
+    var_headers: Vec<VarHeader>,
+    var_header_map: Arc<HashMap<String, VarHeader>>,
+    scripts: Vec<Script>,
+    session_info: String,
+}
+
+/// A scripted telemetry world built with `SyntheticBuilder`.  Cloning is cheap, the scripts are shared.
+#[derive(Clone)]
+pub struct SyntheticTelemetry {
+    inner: Arc<SyntheticInner>,
+}
+
+impl std::fmt::Debug for SyntheticTelemetry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SyntheticTelemetry")
+            .field("header", &self.inner.header)
+            .field("var_headers", &self.inner.var_headers)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SyntheticTelemetry {
+    /// The header, with the tick count of the buffer set to zero.
+    pub fn header(&self) -> Header {
+        self.inner.header
+    }
+
+    /// The variable headers keyed by name, as sent in `UpdatePacket::VariableHeaders`.
+    pub fn var_headers(&self) -> HashMap<String, VarHeader> {
+        (*self.inner.var_header_map).clone()
+    }
+
+    /// The yaml formatted session info string.
+    pub fn session_info(&self) -> &str {
+        &self.inner.session_info
+    }
+
+    /// Computes the telemetry for a tick, with the variable headers attached.
+    pub fn data_at(&self, tick: i32) -> IrData {
+        let mut header = self.inner.header;
+        header.var_buf[0].tick_count = tick;
+        let mut data = vec![0u8; header.buf_len as usize];
+        for (var_header, script) in self.inner.var_headers.iter().zip(&self.inner.scripts) {
+            let offset = var_header.offset as usize;
+            script(tick, &mut data[offset..offset + var_header.size()]);
+        }
+        IrData::new(data, header, tick).with_var_headers(self.inner.var_header_map.clone())
+    }
+
+    /// The packets a client would send for the given ticks: `Connected`, the variable headers, the session info and
+    /// then one data packet per tick.
+    pub fn packets<I>(&self, ticks: I) -> impl Iterator<Item = UpdatePacket>
+    where
+        I: IntoIterator<Item = i32>,
+    {
+        let telemetry = self.clone();
+        [
+            UpdatePacket::Connected {
+                tick_rate: self.inner.header.tick_rate(),
+                header_version: self.inner.header.version(),
+            },
+            UpdatePacket::VariableHeaders(self.var_headers()),
+            UpdatePacket::SessionInfo(self.inner.session_info.clone()),
+        ]
+        .into_iter()
+        .chain(
+            ticks
+                .into_iter()
+                .map(move |tick| UpdatePacket::Data(telemetry.data_at(tick))),
+        )
+    }
+
+    /// A `TelemetrySource` that advances one tick every `1 / tick_rate` seconds, starting now.
+    pub fn source(&self) -> SyntheticSource {
+        SyntheticSource {
+            telemetry: self.clone(),
+            start: Instant::now(),
+        }
+    }
+}
+
+/// Number of past ticks a `SyntheticSource` keeps for `TelemetrySource::get_buffered_data`.
+const BUFFERED_TICKS: i32 = 3;
+
+/// A `TelemetrySource` running a `SyntheticTelemetry` in real time.  Use with `Client::connect_with`.
+#[derive(Debug, Clone)]
+pub struct SyntheticSource {
+    telemetry: SyntheticTelemetry,
+    start: Instant,
+}
+
+impl SyntheticSource {
+    fn tick_rate(&self) -> f64 {
+        self.telemetry.inner.header.tick_rate.max(1) as f64
+    }
+
+    /// Ticks since the source was created, stopping at `i32::MAX` instead of wrapping.
+    fn current_tick(&self) -> i32 {
+        (self.start.elapsed().as_secs_f64() * self.tick_rate()).min(i32::MAX as f64) as i32
+    }
+}
+
+impl TelemetrySource for SyntheticSource {
+    fn header(&self) -> Result<Header> {
+        let mut header = self.telemetry.header();
+        header.var_buf[0].tick_count = self.current_tick();
+        Ok(header)
+    }
+
+    /// Signals when the next tick starts.
+    fn wait_for_update(&self, timeout: Duration) -> bool {
+        let next_tick = (self.current_tick() as f64 + 1.) / self.tick_rate();
+        let until_next = Duration::from_secs_f64(next_tick).saturating_sub(self.start.elapsed());
+        std::thread::sleep(until_next.min(timeout));
+        until_next <= timeout
+    }
+
+    fn get_new_data(&self) -> Result<IrData> {
+        Ok(self.telemetry.data_at(self.current_tick()))
+    }
+
+    /// Keeps the last `BUFFERED_TICKS` ticks, like the rotating buffers of the sim.
+    fn get_buffered_data(&self, after_tick: i32) -> Result<Vec<IrData>> {
+        let current = self.current_tick();
+        let first = after_tick
+            .saturating_add(1)
+            .max(current - BUFFERED_TICKS + 1);
+        Ok((first..=current)
+            .map(|tick| self.telemetry.data_at(tick))
+            .collect())
+    }
+
+    fn get_var_headers(&self) -> Result<Vec<VarHeader>> {
+        Ok(self.telemetry.inner.var_headers.clone())
+    }
+
+    fn session_info(&self) -> Result<String> {
+        Ok(self.telemetry.inner.session_info.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Client;
+
+    fn telemetry() -> SyntheticTelemetry {
+        let mut session = Session::default();
+        session.weekend_info.track_name = "synthetic".to_string();
+        SyntheticBuilder::new(60)
+            .session(&session)
+            .session_clock()
+            .circulating(600)
+            .var("Speed", "m/s", "GPS vehicle speed", |tick| tick as f32)
+            .var("SessionFlags", "irsdk_Flags", "Session flags", |_| {
+                Flags::GREEN_FLAG
+            })
+            .array(
+                "CarIdxOnPitRoad",
+                3,
+                "",
+                "On pit road between the cones",
+                |_, car| car == 1,
+            )
+            .build()
+    }
+
+    #[test]
+    fn test_data_at_follows_scripts() {
+        let telemetry = telemetry();
+        let headers = telemetry.var_headers();
+        let data = telemetry.data_at(900);
+
+        assert_eq!(data.tick(), 900);
+        assert_eq!(data.get::<f32>(headers.get("Speed")), Some(900.));
+        assert_eq!(data.get::<f64>(headers.get("SessionTime")), Some(15.));
+        assert_eq!(data.get::<i32>(headers.get("Lap")), Some(2));
+        assert_eq!(data.get::<f32>(headers.get("LapDistPct")), Some(0.5));
+        assert_eq!(
+            data.get::<Flags>(headers.get("SessionFlags")),
+            Some(Flags::GREEN_FLAG)
+        );
+        assert_eq!(
+            data.get::<Vec<bool>>(headers.get("CarIdxOnPitRoad")),
+            Some(vec![false, true, false])
+        );
+
+        let session: Session = serde_yaml::from_str(telemetry.session_info()).unwrap();
+        assert_eq!(session.weekend_info.track_name, "synthetic");
+    }
+
+    #[test]
+    fn test_replacing_a_variable_keeps_layout_consistent() {
+        let telemetry = SyntheticBuilder::new(60)
+            .var("Speed", "m/s", "", |_| 1.0f64)
+            .var("Gear", "", "", |_| 3)
+            .var("Speed", "m/s", "", |_| 2.0f32)
+            .build();
+        let headers = telemetry.var_headers();
+        let data = telemetry.data_at(0);
+        assert_eq!(data.data().len(), 8);
+        assert_eq!(data.get::<i32>(headers.get("Gear")), Some(3));
+        assert_eq!(data.get::<f32>(headers.get("Speed")), Some(2.));
+    }
+
+    #[test]
+    fn test_client_reads_synthetic_source() {
+        let telemetry = telemetry();
+        let source = telemetry.clone();
+        let rx = Client::connect_with(60., move || Ok(source.source()));
+
+        let mut packets = rx.iter();
+        assert!(matches!(
+            packets.next(),
+            Some(UpdatePacket::Connected { tick_rate: 60, .. })
+        ));
+        let Some(UpdatePacket::VariableHeaders(headers)) = packets.next() else {
+            panic!("Expected variable headers");
+        };
+        assert!(matches!(packets.next(), Some(UpdatePacket::SessionInfo(_))));
+        let Some(UpdatePacket::Data(data)) = packets.next() else {
+            panic!("Expected data");
+        };
+        assert_eq!(
+            data.get::<f32>(headers.get("Speed")),
+            Some(data.tick() as f32)
+        );
+    }
+
+    #[test]
+    fn test_source_ticks_saturate() {
+        let source = SyntheticBuilder::new(i32::MAX).build().source();
+        // Used to divide by a zero tick duration.
+        assert!(source.current_tick() >= 0);
+        assert!(source.wait_for_update(Duration::from_millis(1)));
+
+        let Some(start) = Instant::now().checked_sub(Duration::from_secs(2)) else {
+            return;
+        };
+        // Two seconds are more ticks than fit in an `i32` at this rate.
+        let source = SyntheticSource { start, ..source };
+        assert_eq!(source.current_tick(), i32::MAX);
+        assert_eq!(source.header().unwrap().var_buf[0].tick_count, i32::MAX);
+        assert_eq!(source.get_new_data().unwrap().tick(), i32::MAX);
+    }
+}
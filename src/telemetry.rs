@@ -0,0 +1,290 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::error::{IrError, Result};
+use crate::handle::VarHandle;
+use crate::ir_data::IrData;
+use crate::mapped_file::var_header::VarHeader;
+use crate::vars::VarValue;
+
+/// A struct read from the telemetry, usually implemented with `#[derive(Telemetry)]` from the `derive` feature.
+/// The variables of the fields are looked up once per session with `resolver`, after which `extract_with` only
+/// reads the values.
+pub trait Telemetry: Sized {
+    /// The variables of the fields, resolved against the variable headers of a session.
+    type Resolver;
+
+    /// Looks up the variable of each field.  Fails with `VariableNotFound` if a required variable is missing, with
+    /// `AmbiguousVariable` if a field matches several variables and with `MismatchedType` if a variable can not be
+    /// read as the type of its field.
+    fn resolver(var_headers: &HashMap<String, VarHeader>) -> Result<Self::Resolver>;
+
+    /// Reads the fields from `data`, which must be from the session the resolver was created for.
+    fn extract_with(resolver: &Self::Resolver, data: &IrData) -> Result<Self>;
+
+    /// Resolves the fields against the variable headers attached to `data` and reads them.  Prefer keeping a
+    /// resolver per session with `resolver` when reading every update.
+    fn extract(data: &IrData) -> Result<Self> {
+        let var_headers = data.var_headers().ok_or(IrError::NoVariableHeaders)?;
+        Self::extract_with(&Self::resolver(var_headers)?, data)
+    }
+}
+
+/// The type of a field of a `Telemetry` struct.  Implemented for the types of `VarValue` for single variables,
+/// `Vec` of them for arrays, and `Option` of either for variables that are not in every session.  See
+/// `OptionalField` for `Option<Duration>`.
+pub trait TelemetryField: Sized {
+    /// The resolved variable.
+    type Variable: std::fmt::Debug + Clone;
+
+    /// Checks the variable found for `name`, the variable or field name, or its absence when `header` is `None`.
+    fn resolve(name: &str, header: Option<&VarHeader>) -> Result<Self::Variable>;
+
+    /// Reads the value of the resolved variable.
+    fn read(variable: &Self::Variable, data: &IrData) -> Result<Self>;
+}
+
+/// Checks that the variable exists and is an array, or not, of a type `T` accepts.
+fn check<T: VarValue>(name: &str, header: Option<&VarHeader>, array: bool) -> Result<VarHandle<T>> {
+    let header = header.ok_or_else(|| IrError::VariableNotFound(name.to_string()))?;
+    if (header.count > 1) != array {
+        let requested = if array {
+            std::any::type_name::<Vec<T>>()
+        } else {
+            std::any::type_name::<T>()
+        };
+        return Err(IrError::mismatched(header, requested));
+    }
+    VarHandle::new(header)
+}
+
+impl<T: VarValue> TelemetryField for T {
+    type Variable = VarHandle<T>;
+
+    fn resolve(name: &str, header: Option<&VarHeader>) -> Result<Self::Variable> {
+        check::<T>(name, header, false)
+    }
+
+    fn read(variable: &Self::Variable, data: &IrData) -> Result<Self> {
+        variable.read(data)
+    }
+}
+
+impl<T: VarValue> TelemetryField for Vec<T> {
+    type Variable = VarHandle<T>;
+
+    fn resolve(name: &str, header: Option<&VarHeader>) -> Result<Self::Variable> {
+        check::<T>(name, header, true)
+    }
+
+    fn read(variable: &Self::Variable, data: &IrData) -> Result<Self> {
+        variable.to_vec(data)
+    }
+}
+
+/// A field type whose `Option` is `None` for a variable missing from the session.  Implemented for the
+/// `TelemetryField` types except `Duration`: an `Option<Duration>` field reads the `-1` of lap times that are not set
+/// as `None`, and requires the variable like other `VarValue` types.  `Option<Option<Duration>>` allows both.
+/// Implement it for your own `VarValue` types to read them as optional fields.
+pub trait OptionalField {}
+
+impl OptionalField for u8 {}
+impl OptionalField for bool {}
+impl OptionalField for i32 {}
+impl OptionalField for f32 {}
+impl OptionalField for f64 {}
+impl OptionalField for Option<Duration> {}
+impl<T: VarValue> OptionalField for Vec<T> {}
+
+impl<F: TelemetryField + OptionalField> TelemetryField for Option<F> {
+    type Variable = Option<F::Variable>;
+
+    fn resolve(name: &str, header: Option<&VarHeader>) -> Result<Self::Variable> {
+        header
+            .map(|header| F::resolve(name, Some(header)))
+            .transpose()
+    }
+
+    fn read(variable: &Self::Variable, data: &IrData) -> Result<Self> {
+        variable
+            .as_ref()
+            .map(|variable| F::read(variable, data))
+            .transpose()
+    }
+}
+
+/// Finds the variable of a field.  With `exact`, `name` is the name of the variable.  Otherwise it is the name of the
+/// field, which matches a variable ignoring case and underscores, so `lap_dist_pct` finds `LapDistPct` and `rpm`
+/// finds `RPM`.  A variable named exactly `name` is preferred, otherwise more than one match fails with
+/// `AmbiguousVariable`.
+#[doc(hidden)]
+pub fn find_variable<'a>(
+    var_headers: &'a HashMap<String, VarHeader>,
+    name: &str,
+    exact: bool,
+) -> Result<Option<&'a VarHeader>> {
+    if exact || var_headers.contains_key(name) {
+        return Ok(var_headers.get(name));
+    }
+    let normalize = |name: &str| {
+        name.chars()
+            .filter(|c| *c != '_')
+            .map(|c| c.to_ascii_lowercase())
+            .collect::<String>()
+    };
+    let field = normalize(name);
+    let mut matches = var_headers
+        .values()
+        .filter(|h| normalize(h.name()) == field)
+        .collect::<Vec<_>>();
+    if matches.len() > 1 {
+        let mut variables = matches
+            .iter()
+            .map(|h| h.name().to_string())
+            .collect::<Vec<_>>();
+        variables.sort();
+        return Err(IrError::AmbiguousVariable {
+            name: name.to_string(),
+            variables,
+        });
+    }
+    Ok(matches.pop())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapped_file::values::DataVarType;
+    use crate::synthetic::SyntheticBuilder;
+    use crate::types::TrackLocation;
+
+    #[derive(Debug)]
+    struct Car {
+        speed: f32,
+        lap_dist_pct: Vec<f32>,
+        gear: Option<i32>,
+    }
+
+    struct CarResolver {
+        speed: VarHandle<f32>,
+        lap_dist_pct: VarHandle<f32>,
+        gear: Option<VarHandle<i32>>,
+    }
+
+    // What the derive macro generates.
+    impl Telemetry for Car {
+        type Resolver = CarResolver;
+
+        fn resolver(var_headers: &HashMap<String, VarHeader>) -> Result<Self::Resolver> {
+            let find = |name| find_variable(var_headers, name, false);
+            Ok(CarResolver {
+                speed: f32::resolve("speed", find("speed")?)?,
+                lap_dist_pct: Vec::<f32>::resolve("CarIdxLapDistPct", {
+                    find_variable(var_headers, "CarIdxLapDistPct", true)?
+                })?,
+                gear: Option::<i32>::resolve("gear", find("gear")?)?,
+            })
+        }
+
+        fn extract_with(resolver: &Self::Resolver, data: &IrData) -> Result<Self> {
+            Ok(Car {
+                speed: TelemetryField::read(&resolver.speed, data)?,
+                lap_dist_pct: TelemetryField::read(&resolver.lap_dist_pct, data)?,
+                gear: TelemetryField::read(&resolver.gear, data)?,
+            })
+        }
+    }
+
+    #[test]
+    fn test_extract_checks_variables() {
+        let telemetry = SyntheticBuilder::new(60)
+            .var("Speed", "m/s", "GPS vehicle speed", |_| 42.5f32)
+            .array("CarIdxLapDistPct", 4, "%", "Lap distance", |_, i| {
+                i as f32 / 4.
+            })
+            .build();
+        let car = Car::extract(&telemetry.data_at(0)).unwrap();
+        assert_eq!(car.speed, 42.5);
+        assert_eq!(car.lap_dist_pct, vec![0., 0.25, 0.5, 0.75]);
+        assert_eq!(car.gear, None);
+
+        let var_headers = telemetry.var_headers();
+        assert!(matches!(
+            i32::resolve("speed", find_variable(&var_headers, "speed", false).unwrap()),
+            Err(IrError::MismatchedType { ref name, var_type: DataVarType::Float, .. }) if name == "Speed"
+        ));
+        assert!(matches!(
+            Vec::<TrackLocation>::resolve("CarIdxTrackSurface", None),
+            Err(IrError::VariableNotFound(ref name)) if name == "CarIdxTrackSurface"
+        ));
+    }
+
+    #[test]
+    fn test_find_variable_rejects_ambiguous_fields() {
+        let telemetry = SyntheticBuilder::new(60)
+            .var("LapDist", "m", "Meters traveled from S/F this lap", |_| {
+                1f32
+            })
+            .var("Lap_Dist", "m", "Not a real variable", |_| 2f32)
+            .var("RPM", "revs/min", "Engine rpm", |_| 3f32)
+            .build();
+        let var_headers = telemetry.var_headers();
+        assert_eq!(
+            find_variable(&var_headers, "rpm", false)
+                .unwrap()
+                .unwrap()
+                .name(),
+            "RPM"
+        );
+        // An exact match is not ambiguous.
+        assert_eq!(
+            find_variable(&var_headers, "LapDist", false)
+                .unwrap()
+                .unwrap()
+                .name(),
+            "LapDist"
+        );
+        let error = find_variable(&var_headers, "lap_dist", false).unwrap_err();
+        assert!(matches!(
+            error,
+            IrError::AmbiguousVariable { ref name, ref variables }
+                if name == "lap_dist" && variables == &["LapDist", "Lap_Dist"]
+        ));
+        assert_eq!(
+            error.to_string(),
+            "Field lap_dist matches each of the variables LapDist, Lap_Dist"
+        );
+    }
+
+    #[test]
+    fn test_optional_durations() {
+        let telemetry = SyntheticBuilder::new(60)
+            .var("LapLastLapTime", "s", "Players last lap time", |_| -1f32)
+            .build();
+        let var_headers = telemetry.var_headers();
+        let data = telemetry.data_at(0);
+        let last = var_headers.get("LapLastLapTime");
+        // Lap times that are not set are `None`, but the variable is required.
+        let variable = Option::<Duration>::resolve("LapLastLapTime", last).unwrap();
+        assert_eq!(Option::<Duration>::read(&variable, &data).unwrap(), None);
+        assert!(matches!(
+            Option::<Duration>::resolve("LapBestLapTime", None),
+            Err(IrError::VariableNotFound(_))
+        ));
+        let variable = Option::<Option<Duration>>::resolve("LapLastLapTime", last).unwrap();
+        assert_eq!(
+            Option::<Option<Duration>>::read(&variable, &data).unwrap(),
+            Some(None)
+        );
+        let missing = Option::<Option<Duration>>::resolve("LapBestLapTime", None).unwrap();
+        assert_eq!(
+            Option::<Option<Duration>>::read(&missing, &data).unwrap(),
+            None
+        );
+        let variable = Duration::resolve("LapLastLapTime", last).unwrap();
+        assert!(matches!(
+            Duration::read(&variable, &data),
+            Err(IrError::ExceedsVariableRange)
+        ));
+    }
+}
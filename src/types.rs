@@ -1,8 +1,10 @@
 use bitflags::bitflags;
 use num::{FromPrimitive, ToPrimitive};
-use num_derive::FromPrimitive;
-use serde::Serialize;
+use serde::de::Visitor;
+use serde::{Deserialize, Serialize};
 use std::fmt::Display;
+use std::marker::PhantomData;
+use std::time::Duration;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum IrValue {
@@ -127,6 +129,35 @@ where
     }
 }
 
+/// Seconds of a time variable.
+fn seconds(value: IrValue) -> Result<f64, crate::error::IrError> {
+    match value {
+        IrValue::Float(value) => Ok(value as f64),
+        IrValue::Double(value) => Ok(value),
+        _ => Err(crate::error::IrError::TypeError),
+    }
+}
+
+impl TryFrom<IrValue> for Duration {
+    type Error = crate::error::IrError;
+
+    /// Fails for negative times, like the `-1` iRacing writes when there is no time.  Read an `Option<Duration>` to
+    /// get `None` for those instead.
+    fn try_from(value: IrValue) -> Result<Self, Self::Error> {
+        Duration::try_from_secs_f64(seconds(value)?)
+            .map_err(|_| crate::error::IrError::ExceedsVariableRange)
+    }
+}
+
+impl TryFrom<IrValue> for Option<Duration> {
+    type Error = crate::error::IrError;
+
+    /// `None` for the `-1` "no time" value and other negative or invalid times, like `LapTime::from_secs`.
+    fn try_from(value: IrValue) -> Result<Self, Self::Error> {
+        Ok(Duration::try_from_secs_f64(seconds(value)?).ok())
+    }
+}
+
 macro_rules! impl_tryfrom_ir_value_enum {
     ($type:ty) => {
         impl TryFrom<IrValue> for $type {
@@ -134,10 +165,7 @@ macro_rules! impl_tryfrom_ir_value_enum {
 
             fn try_from(value: IrValue) -> Result<Self, Self::Error> {
                 match value {
-                    IrValue::Int(v) => <$type>::from_i32(v).ok_or(crate::error::IrError::TypeError),
-                    IrValue::BitField(v) => {
-                        <$type>::from_i32(v).ok_or(crate::error::IrError::TypeError)
-                    }
+                    IrValue::Int(v) | IrValue::BitField(v) => Ok(<$type>::from_code(v)),
                     _ => Err(crate::error::IrError::TypeError),
                 }
             }
@@ -145,247 +173,278 @@ macro_rules! impl_tryfrom_ir_value_enum {
     };
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, FromPrimitive, Serialize)]
-pub enum TrackLocation {
-    NotInWorld = -1,
-    OffTrack = 0,
-    InPitStall,
-    AproachingPits,
-    OnTrack,
+/// Declares an enum of the SDK with the code of each variant.  Codes the enum does not know, like a surface added in
+/// a newer version of the sim, are kept as `Unknown(code)` instead of failing the conversion.  Known values display
+/// and serialize as the name of their variant (or the name after `as`), unknown values display as `Unknown(code)`
+/// and serialize as the code, and both parse and deserialize back.
+macro_rules! sdk_enum {
+    (@name $variant:ident) => {
+        stringify!($variant)
+    };
+    (@name $variant:ident $name:literal) => {
+        $name
+    };
+    (
+        $(#[$meta:meta])*
+        pub enum $type:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $code:literal $(as $name:literal)?,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $type {
+            $($(#[$variant_meta])* $variant,)*
+            /// A code this version of the crate does not know.
+            Unknown(i32),
+        }
+
+        impl $type {
+            /// The value of an SDK code, `Unknown` for codes that are not known.
+            pub fn from_code(code: i32) -> Self {
+                match code {
+                    $($code => $type::$variant,)*
+                    code => $type::Unknown(code),
+                }
+            }
+
+            /// The SDK code of the value.
+            pub fn code(&self) -> i32 {
+                match self {
+                    $($type::$variant => $code,)*
+                    $type::Unknown(code) => *code,
+                }
+            }
+
+            /// Returns true for codes the enum does not know.
+            pub fn is_unknown(&self) -> bool {
+                matches!(self, $type::Unknown(_))
+            }
+
+            /// The name the value serializes as, `None` for unknown codes.
+            fn serde_name(&self) -> Option<&'static str> {
+                match self {
+                    $($type::$variant => Some(sdk_enum!(@name $variant $($name)?)),)*
+                    $type::Unknown(_) => None,
+                }
+            }
+        }
+
+        impl From<i32> for $type {
+            fn from(code: i32) -> Self {
+                <$type>::from_code(code)
+            }
+        }
+
+        impl From<$type> for i32 {
+            fn from(value: $type) -> Self {
+                value.code()
+            }
+        }
+
+        impl FromPrimitive for $type {
+            fn from_i64(n: i64) -> Option<Self> {
+                i32::try_from(n).ok().map(<$type>::from_code)
+            }
+
+            fn from_u64(n: u64) -> Option<Self> {
+                i32::try_from(n).ok().map(<$type>::from_code)
+            }
+        }
+
+        impl Display for $type {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    $($type::$variant => write!(f, "{}", stringify!($variant)),)*
+                    $type::Unknown(code) => write!(f, "Unknown({})", code),
+                }
+            }
+        }
+
+        impl std::str::FromStr for $type {
+            type Err = crate::error::IrError;
+
+            /// Parses the display or serde name of a value, `Unknown(code)` or the code itself.
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                $(
+                    if value == stringify!($variant) || value == sdk_enum!(@name $variant $($name)?) {
+                        return Ok($type::$variant);
+                    }
+                )*
+                parse_code(value).map(<$type>::from_code)
+            }
+        }
+
+        impl Serialize for $type {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                match self.serde_name() {
+                    Some(name) => serializer.serialize_str(name),
+                    None => serializer.serialize_i32(self.code()),
+                }
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_any(CodeVisitor(PhantomData))
+            }
+        }
+
+        impl_tryfrom_ir_value_enum!($type);
+    };
+}
+
+/// Parses `Unknown(code)` or a bare code.
+fn parse_code(value: &str) -> Result<i32, crate::error::IrError> {
+    value
+        .strip_prefix("Unknown(")
+        .and_then(|code| code.strip_suffix(')'))
+        .unwrap_or(value)
+        .trim()
+        .parse()
+        .map_err(|_| crate::error::IrError::TypeError)
 }
 
-impl_tryfrom_ir_value_enum!(TrackLocation);
+/// Deserializes an SDK enum from its name or its code.
+struct CodeVisitor<T>(PhantomData<T>);
 
-impl Display for TrackLocation {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let location = match self {
-            TrackLocation::NotInWorld => "NotInWorld",
-            TrackLocation::OffTrack => "OffTrack",
-            TrackLocation::InPitStall => "InPitStall",
-            TrackLocation::AproachingPits => "AproachingPits",
-            TrackLocation::OnTrack => "OnTrack",
-        };
-        write!(f, "{}", location)
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, FromPrimitive, Serialize)]
-pub enum TrackSurface {
-    SurfaceNotInWorld = -1,
-    UndefinedMaterial = 0,
-
-    Asphalt1Material,
-    Asphalt2Material,
-    Asphalt3Material,
-    Asphalt4Material,
-    Concrete1Material,
-    Concrete2Material,
-    RacingDirt1Material,
-    RacingDirt2Material,
-    Paint1Material,
-    Paint2Material,
-    Rumble1Material,
-    Rumble2Material,
-    Rumble3Material,
-    Rumble4Material,
-    Grass1Material,
-    Grass2Material,
-    Grass3Material,
-    Grass4Material,
-    Dirt1Material,
-    Dirt2Material,
-    Dirt3Material,
-    Dirt4Material,
-    SandMaterial,
-    Gravel1Material,
-    Gravel2Material,
-    GrasscreteMaterial,
-    AstroturfMaterial,
-}
-
-impl_tryfrom_ir_value_enum!(TrackSurface);
-
-impl Display for TrackSurface {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let surface = match self {
-            TrackSurface::SurfaceNotInWorld => "SurfaceNotInWorld",
-            TrackSurface::UndefinedMaterial => "UndefinedMaterial",
-            TrackSurface::Asphalt1Material => "Asphalt1Material",
-            TrackSurface::Asphalt2Material => "Asphalt2Material",
-            TrackSurface::Asphalt3Material => "Asphalt3Material",
-            TrackSurface::Asphalt4Material => "Asphalt4Material",
-            TrackSurface::Concrete1Material => "Concrete1Material",
-            TrackSurface::Concrete2Material => "Concrete2Material",
-            TrackSurface::RacingDirt1Material => "RacingDirt1Material",
-            TrackSurface::RacingDirt2Material => "RacingDirt2Material",
-            TrackSurface::Paint1Material => "Paint1Material",
-            TrackSurface::Paint2Material => "Paint2Material",
-            TrackSurface::Rumble1Material => "Rumble1Material",
-            TrackSurface::Rumble2Material => "Rumble2Material",
-            TrackSurface::Rumble3Material => "Rumble3Material",
-            TrackSurface::Rumble4Material => "Rumble4Material",
-            TrackSurface::Grass1Material => "Grass1Material",
-            TrackSurface::Grass2Material => "Grass2Material",
-            TrackSurface::Grass3Material => "Grass3Material",
-            TrackSurface::Grass4Material => "Grass4Material",
-            TrackSurface::Dirt1Material => "Dirt1Material",
-            TrackSurface::Dirt2Material => "Dirt2Material",
-            TrackSurface::Dirt3Material => "Dirt3Material",
-            TrackSurface::Dirt4Material => "Dirt4Material",
-            TrackSurface::SandMaterial => "SandMaterial",
-            TrackSurface::Gravel1Material => "Gravel1Material",
-            TrackSurface::Gravel2Material => "Gravel2Material",
-            TrackSurface::GrasscreteMaterial => "GrasscreteMaterial",
-            TrackSurface::AstroturfMaterial => "AstroturfMaterial",
-        };
-        write!(f, "{}", surface)
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, FromPrimitive, Serialize)]
-pub enum SessionState {
-    Invalid = 0,
-    GetInCar,
-    Warmup,
-    ParadeLaps,
-    Racing,
-    Checkered,
-    CoolDown,
-}
-
-impl_tryfrom_ir_value_enum!(SessionState);
-
-impl Display for SessionState {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let state = match self {
-            SessionState::Invalid => "Invalid",
-            SessionState::GetInCar => "GetInCar",
-            SessionState::Warmup => "Warmup",
-            SessionState::ParadeLaps => "ParadeLaps",
-            SessionState::Racing => "Racing",
-            SessionState::Checkered => "Checkered",
-            SessionState::CoolDown => "CoolDown",
-        };
-        write!(f, "{}", state)
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, FromPrimitive, Serialize)]
-pub enum CarLeftRight {
-    Off = 0,
-    Clear,    // no cars around us.
-    CarLeft,  // there is a car to our left.
-    CarRight, // there is a car to our right.
-    #[allow(clippy::enum_variant_names)]
-    CarLeftRight, // there are cars on each side.
-    TwoCarsLeft, // there are two cars to our left.
-    TwoCarsRight, // there are two cars to our right.
-}
-
-impl_tryfrom_ir_value_enum!(CarLeftRight);
-
-impl Display for CarLeftRight {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let state = match self {
-            CarLeftRight::Off => "Off",
-            CarLeftRight::Clear => "Clear",
-            CarLeftRight::CarLeft => "CarLeft",
-            CarLeftRight::CarRight => "CarRight",
-            CarLeftRight::CarLeftRight => "CarLeftRight",
-            CarLeftRight::TwoCarsLeft => "TwoCarsLeft",
-            CarLeftRight::TwoCarsRight => "TwoCarsRight",
-        };
-        write!(f, "{}", state)
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, FromPrimitive, Serialize)]
-pub enum PitStatus {
-    // status
-    #[serde(rename = "None")]
-    NoStatus = 0, // renamed from None from SDK to avoid confusion with Option
-    InProgress,
-    Complete,
-
-    // errors
-    TooFarLeft = 100,
-    TooFarRight,
-    TooFarForward,
-    TooFarBack,
-    BadAngle,
-    CantFixThat,
-}
-
-impl_tryfrom_ir_value_enum!(PitStatus);
-
-impl Display for PitStatus {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let state = match self {
-            PitStatus::NoStatus => "NoStatus",
-            PitStatus::InProgress => "InProgress",
-            PitStatus::Complete => "Complete",
-            PitStatus::TooFarLeft => "TooFarLeft",
-            PitStatus::TooFarRight => "TooFarRight",
-            PitStatus::TooFarForward => "TooFarForward",
-            PitStatus::TooFarBack => "TooFarBack",
-            PitStatus::BadAngle => "BadAngle",
-            PitStatus::CantFixThat => "CantFixThat",
-        };
-        write!(f, "{}", state)
+impl<T> Visitor<'_> for CodeVisitor<T>
+where
+    T: std::str::FromStr + From<i32>,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "the name or code of a value")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<T, E> {
+        value
+            .parse()
+            .map_err(|_| E::invalid_value(serde::de::Unexpected::Str(value), &self))
+    }
+
+    fn visit_i64<E: serde::de::Error>(self, value: i64) -> Result<T, E> {
+        i32::try_from(value)
+            .map(T::from)
+            .map_err(|_| E::invalid_value(serde::de::Unexpected::Signed(value), &self))
+    }
+
+    fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<T, E> {
+        i32::try_from(value)
+            .map(T::from)
+            .map_err(|_| E::invalid_value(serde::de::Unexpected::Unsigned(value), &self))
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, FromPrimitive, Serialize)]
-pub enum PaceMode {
-    SingleFileStart = 0,
-    DoubleFileStart,
-    SingleFileRestart,
-    DoubleFileRestart,
-    NotPacing,
+sdk_enum! {
+    pub enum TrackLocation {
+        NotInWorld = -1,
+        OffTrack = 0,
+        InPitStall = 1,
+        AproachingPits = 2,
+        OnTrack = 3,
+    }
 }
 
-impl_tryfrom_ir_value_enum!(PaceMode);
+sdk_enum! {
+    pub enum TrackSurface {
+        SurfaceNotInWorld = -1,
+        UndefinedMaterial = 0,
+
+        Asphalt1Material = 1,
+        Asphalt2Material = 2,
+        Asphalt3Material = 3,
+        Asphalt4Material = 4,
+        Concrete1Material = 5,
+        Concrete2Material = 6,
+        RacingDirt1Material = 7,
+        RacingDirt2Material = 8,
+        Paint1Material = 9,
+        Paint2Material = 10,
+        Rumble1Material = 11,
+        Rumble2Material = 12,
+        Rumble3Material = 13,
+        Rumble4Material = 14,
+        Grass1Material = 15,
+        Grass2Material = 16,
+        Grass3Material = 17,
+        Grass4Material = 18,
+        Dirt1Material = 19,
+        Dirt2Material = 20,
+        Dirt3Material = 21,
+        Dirt4Material = 22,
+        SandMaterial = 23,
+        Gravel1Material = 24,
+        Gravel2Material = 25,
+        GrasscreteMaterial = 26,
+        AstroturfMaterial = 27,
+    }
+}
 
-impl Display for PaceMode {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let state = match self {
-            PaceMode::SingleFileStart => "SingleFileStart",
-            PaceMode::DoubleFileStart => "DoubleFileStart",
-            PaceMode::SingleFileRestart => "SingleFileRestart",
-            PaceMode::DoubleFileRestart => "DoubleFileRestart",
-            PaceMode::NotPacing => "NotPacing",
-        };
-        write!(f, "{}", state)
+sdk_enum! {
+    pub enum SessionState {
+        Invalid = 0,
+        GetInCar = 1,
+        Warmup = 2,
+        ParadeLaps = 3,
+        Racing = 4,
+        Checkered = 5,
+        CoolDown = 6,
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, FromPrimitive, Serialize)]
-pub enum TrackWetness {
-    Unknown = 0,
-    Dry,
-    MostlyDry,
-    VeryLightlyWet,
-    LightlyWet,
-    ModeratelyWet,
-    VeryWet,
-    ExtremelyWet,
+sdk_enum! {
+    pub enum CarLeftRight {
+        Off = 0,
+        Clear = 1,    // no cars around us.
+        CarLeft = 2,  // there is a car to our left.
+        CarRight = 3, // there is a car to our right.
+        #[allow(clippy::enum_variant_names)]
+        CarLeftRight = 4, // there are cars on each side.
+        TwoCarsLeft = 5, // there are two cars to our left.
+        TwoCarsRight = 6, // there are two cars to our right.
+    }
 }
 
-impl_tryfrom_ir_value_enum!(TrackWetness);
+sdk_enum! {
+    pub enum PitStatus {
+        // status
+        NoStatus = 0 as "None", // renamed from None from SDK to avoid confusion with Option
+        InProgress = 1,
+        Complete = 2,
+
+        // errors
+        TooFarLeft = 100,
+        TooFarRight = 101,
+        TooFarForward = 102,
+        TooFarBack = 103,
+        BadAngle = 104,
+        CantFixThat = 105,
+    }
+}
 
-impl Display for TrackWetness {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let state = match self {
-            TrackWetness::Unknown => "Unknown",
-            TrackWetness::Dry => "Dry",
-            TrackWetness::MostlyDry => "MostlyDry",
-            TrackWetness::VeryLightlyWet => "VeryLightlyWet",
-            TrackWetness::LightlyWet => "LightlyWet",
-            TrackWetness::ModeratelyWet => "ModeratelyWet",
-            TrackWetness::VeryWet => "VeryWet",
-            TrackWetness::ExtremelyWet => "ExtremelyWet",
-        };
-        write!(f, "{}", state)
+sdk_enum! {
+    pub enum PaceMode {
+        SingleFileStart = 0,
+        DoubleFileStart = 1,
+        SingleFileRestart = 2,
+        DoubleFileRestart = 3,
+        NotPacing = 4,
+    }
+}
+
+sdk_enum! {
+    pub enum TrackWetness {
+        /// `UNKNOWN` in the SDK, renamed to not be confused with `TrackWetness::Unknown(code)`.  It still serializes
+        /// and parses as `"Unknown"`, but displays as `Undefined`.
+        Undefined = 0 as "Unknown",
+        Dry = 1,
+        MostlyDry = 2,
+        VeryLightlyWet = 3,
+        LightlyWet = 4,
+        ModeratelyWet = 5,
+        VeryWet = 6,
+        ExtremelyWet = 7,
     }
 }
 
@@ -396,8 +455,8 @@ macro_rules! impl_tryfrom_ir_value_bitflags {
 
             fn try_from(value: IrValue) -> Result<Self, Self::Error> {
                 match value {
-                    IrValue::BitField(v) => Ok(Self::from_bits_truncate(v as u32)),
-                    IrValue::Int(v) => Ok(Self::from_bits_truncate(v as u32)),
+                    IrValue::BitField(v) => Ok(Self::from_bits_retain(v as u32)),
+                    IrValue::Int(v) => Ok(Self::from_bits_retain(v as u32)),
                     _ => Err(crate::error::IrError::TypeError),
                 }
             }
@@ -415,6 +474,8 @@ bitflags! {
         const PIT_SPEED_LIMITER	 = 0x0010;
         const REV_LIMITER_ACTIVE = 0x0020;
         const OIL_TEMPERATURE	 = 0x0040;
+        const MANDATORY_REPAIR_NEEDED = 0x0080;
+        const OPTIONAL_REPAIR_NEEDED  = 0x0100;
     }
 }
 
@@ -444,6 +505,12 @@ impl Display for EngineWarnings {
         if self.contains(Self::OIL_TEMPERATURE) {
             warnings.push("Oil Temperature");
         }
+        if self.contains(Self::MANDATORY_REPAIR_NEEDED) {
+            warnings.push("Mandatory Repair Needed");
+        }
+        if self.contains(Self::OPTIONAL_REPAIR_NEEDED) {
+            warnings.push("Optional Repair Needed");
+        }
         write!(f, "{}", warnings.join(", "))
     }
 }
@@ -621,6 +688,7 @@ impl Display for CameraState {
 }
 
 bitflags! {
+    /// `irsdk_PitSvFlags`.  These are all the bits the SDK defines, `FAST_REPAIR` being the last one.
     #[derive(Debug, Copy, Clone, PartialEq, Serialize)]
     pub struct PitServiceFlags: u32 {
         const LF_TIRE_CHANGE     = 0x0001;
@@ -688,3 +756,121 @@ impl Display for PaceFlags {
         write!(f, "{}", flags.join(", "))
     }
 }
+
+sdk_enum! {
+    /// The incident reported in the first byte of `IncidentFlags`.
+    pub enum IncidentReport {
+        NoReport = 0,
+        OutOfControl = 1,
+        OffTrack = 2,
+        OffTrackOngoing = 3, // not used
+        ContactWithWorld = 4,
+        CollisionWithWorld = 5,
+        CollisionWithWorldOngoing = 6, // not used
+        ContactWithCar = 7,
+        CollisionWithCar = 8,
+    }
+}
+
+sdk_enum! {
+    /// The penalty in the second byte of `IncidentFlags`, shifted down a byte.
+    pub enum IncidentPenalty {
+        NoReport = 0,
+        ZeroX = 1,
+        OneX = 2,
+        TwoX = 3,
+        FourX = 4,
+    }
+}
+
+/// The last incident of the player, like `PlayerIncidents`.  The SDK packs the report in the first byte and the
+/// penalty in the second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct IncidentFlags {
+    pub report: IncidentReport,
+    pub penalty: IncidentPenalty,
+}
+
+impl IncidentFlags {
+    const REPORT_MASK: i32 = 0x00ff;
+    const PENALTY_MASK: i32 = 0xff00;
+
+    pub fn from_code(code: i32) -> Self {
+        Self {
+            report: IncidentReport::from_code(code & Self::REPORT_MASK),
+            penalty: IncidentPenalty::from_code((code & Self::PENALTY_MASK) >> 8),
+        }
+    }
+
+    /// The packed SDK code.
+    pub fn code(&self) -> i32 {
+        (self.report.code() & Self::REPORT_MASK) | ((self.penalty.code() << 8) & Self::PENALTY_MASK)
+    }
+}
+
+impl_tryfrom_ir_value_enum!(IncidentFlags);
+
+impl Display for IncidentFlags {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}, {}", self.report, self.penalty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_codes_round_trip() {
+        let surface = TrackSurface::try_from(IrValue::Int(42)).unwrap();
+        assert_eq!(surface, TrackSurface::Unknown(42));
+        assert_eq!(surface.code(), 42);
+        assert_eq!(surface.to_string(), "Unknown(42)");
+        assert_eq!("Unknown(42)".parse::<TrackSurface>().unwrap(), surface);
+        assert_eq!(serde_json::to_string(&surface).unwrap(), "42");
+        assert_eq!(serde_json::from_str::<TrackSurface>("42").unwrap(), surface);
+
+        let status = PitStatus::from_code(100);
+        assert_eq!(status, PitStatus::TooFarLeft);
+        assert_eq!(i32::from(PitStatus::NoStatus), 0);
+        assert_eq!(
+            serde_json::to_string(&PitStatus::NoStatus).unwrap(),
+            r#""None""#
+        );
+        assert_eq!(
+            serde_json::from_str::<PitStatus>(r#""None""#).unwrap(),
+            PitStatus::NoStatus
+        );
+        assert_eq!(
+            "NoStatus".parse::<PitStatus>().unwrap(),
+            PitStatus::NoStatus
+        );
+        assert_eq!(TrackLocation::from_code(-1), TrackLocation::NotInWorld);
+        assert!("Wet".parse::<TrackWetness>().is_err());
+        assert_eq!(
+            serde_json::to_string(&TrackWetness::Undefined).unwrap(),
+            "\"Unknown\""
+        );
+        assert_eq!(
+            "Unknown".parse::<TrackWetness>().unwrap(),
+            TrackWetness::Undefined
+        );
+        assert_eq!(TrackWetness::Undefined.to_string(), "Undefined");
+        assert_eq!(
+            "Unknown(9)".parse::<TrackWetness>().unwrap(),
+            TrackWetness::Unknown(9)
+        );
+
+        assert_eq!(PitServiceFlags::all().bits(), 0x7f);
+
+        let flags = EngineWarnings::try_from(IrValue::BitField(0x8001)).unwrap();
+        assert!(flags.contains(EngineWarnings::WATER_TEMP));
+        assert_eq!(flags.bits(), 0x8001);
+
+        let incident = IncidentFlags::try_from(IrValue::BitField(0x0202)).unwrap();
+        assert_eq!(incident.report, IncidentReport::OffTrack);
+        assert_eq!(incident.penalty, IncidentPenalty::OneX);
+        assert_eq!(incident.code(), 0x0202);
+        assert_eq!(incident.to_string(), "OffTrack, OneX");
+    }
+}
@@ -0,0 +1,290 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::error::IrError;
+
+/// A unit of measurement used by iRacing for variables (`VarHeader::units`) and in the session info
+/// (`Measurement`), plus the imperial units they convert to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[non_exhaustive]
+pub enum Unit {
+    MetersPerSecond,
+    KilometersPerHour,
+    MilesPerHour,
+    Celsius,
+    Fahrenheit,
+    Pascals,
+    Kilopascals,
+    Bar,
+    Psi,
+    InchesOfMercury,
+    Liters,
+    Gallons,
+    Radians,
+    Degrees,
+    RadiansPerSecond,
+    DegreesPerSecond,
+    Millimeters,
+    Meters,
+    Kilometers,
+    Feet,
+    Miles,
+    Kilograms,
+    Pounds,
+    Seconds,
+    Percent,
+    RevsPerMinute,
+    Volts,
+    Newtons,
+    NewtonMeters,
+    MetersPerSecondSquared,
+    StandardGravity,
+    KilogramsPerHour,
+    PoundsPerHour,
+}
+
+/// What a unit measures.  Only units of the same dimension convert to each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Speed,
+    Temperature,
+    Pressure,
+    Volume,
+    Angle,
+    AngularVelocity,
+    Distance,
+    Mass,
+    Time,
+    Ratio,
+    RotationRate,
+    Voltage,
+    Force,
+    Torque,
+    Acceleration,
+    MassFlow,
+}
+
+impl Unit {
+    /// Parses the unit of a variable or measurement, like `m/s` or `kPa`.  Returns `None` for units without
+    /// conversions: the `irsdk_*` names of flags and enums, `s/s`, `Hz`, `fps`, `kg/m^3` and `l or kWh`.
+    pub fn parse(unit: &str) -> Option<Self> {
+        // The 360 Hz variables have units like `m/s at 360 Hz`.
+        let unit = unit.split(" at ").next().unwrap_or(unit).trim();
+        let unit = match unit {
+            "m/s" => Unit::MetersPerSecond,
+            "km/h" | "kph" => Unit::KilometersPerHour,
+            "mph" => Unit::MilesPerHour,
+            "C" => Unit::Celsius,
+            "F" => Unit::Fahrenheit,
+            "Pa" => Unit::Pascals,
+            "kPa" => Unit::Kilopascals,
+            "bar" => Unit::Bar,
+            "psi" => Unit::Psi,
+            "Hg" | "inHg" => Unit::InchesOfMercury,
+            "l" => Unit::Liters,
+            "gal" => Unit::Gallons,
+            "rad" => Unit::Radians,
+            "deg" => Unit::Degrees,
+            "rad/s" => Unit::RadiansPerSecond,
+            "deg/s" => Unit::DegreesPerSecond,
+            "mm" => Unit::Millimeters,
+            "m" => Unit::Meters,
+            "km" => Unit::Kilometers,
+            "ft" => Unit::Feet,
+            "mi" => Unit::Miles,
+            "kg" => Unit::Kilograms,
+            "lb" | "lbs" => Unit::Pounds,
+            "s" | "sec" => Unit::Seconds,
+            "%" => Unit::Percent,
+            "revs/min" | "RPM" | "rpm" => Unit::RevsPerMinute,
+            "V" => Unit::Volts,
+            "N" => Unit::Newtons,
+            "N*m" | "Nm" => Unit::NewtonMeters,
+            "m/s^2" => Unit::MetersPerSecondSquared,
+            "G" => Unit::StandardGravity,
+            "kg/h" => Unit::KilogramsPerHour,
+            "lb/h" => Unit::PoundsPerHour,
+            _ => return None,
+        };
+        Some(unit)
+    }
+
+    /// The symbol of the unit, as iRacing writes it where it uses the unit.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::MetersPerSecond => "m/s",
+            Unit::KilometersPerHour => "km/h",
+            Unit::MilesPerHour => "mph",
+            Unit::Celsius => "C",
+            Unit::Fahrenheit => "F",
+            Unit::Pascals => "Pa",
+            Unit::Kilopascals => "kPa",
+            Unit::Bar => "bar",
+            Unit::Psi => "psi",
+            Unit::InchesOfMercury => "Hg",
+            Unit::Liters => "l",
+            Unit::Gallons => "gal",
+            Unit::Radians => "rad",
+            Unit::Degrees => "deg",
+            Unit::RadiansPerSecond => "rad/s",
+            Unit::DegreesPerSecond => "deg/s",
+            Unit::Millimeters => "mm",
+            Unit::Meters => "m",
+            Unit::Kilometers => "km",
+            Unit::Feet => "ft",
+            Unit::Miles => "mi",
+            Unit::Kilograms => "kg",
+            Unit::Pounds => "lb",
+            Unit::Seconds => "s",
+            Unit::Percent => "%",
+            Unit::RevsPerMinute => "revs/min",
+            Unit::Volts => "V",
+            Unit::Newtons => "N",
+            Unit::NewtonMeters => "N*m",
+            Unit::MetersPerSecondSquared => "m/s^2",
+            Unit::StandardGravity => "G",
+            Unit::KilogramsPerHour => "kg/h",
+            Unit::PoundsPerHour => "lb/h",
+        }
+    }
+
+    /// The dimension, and the scale and offset from the unit to the base unit of the dimension:
+    /// `base = value * scale + offset`.
+    fn definition(&self) -> (Dimension, f64, f64) {
+        use std::f64::consts::PI;
+        match self {
+            Unit::MetersPerSecond => (Dimension::Speed, 1., 0.),
+            Unit::KilometersPerHour => (Dimension::Speed, 1. / 3.6, 0.),
+            Unit::MilesPerHour => (Dimension::Speed, 0.44704, 0.),
+            Unit::Celsius => (Dimension::Temperature, 1., 0.),
+            Unit::Fahrenheit => (Dimension::Temperature, 5. / 9., -32. * 5. / 9.),
+            Unit::Pascals => (Dimension::Pressure, 0.001, 0.),
+            Unit::Kilopascals => (Dimension::Pressure, 1., 0.),
+            Unit::Bar => (Dimension::Pressure, 100., 0.),
+            Unit::Psi => (Dimension::Pressure, 6.894_757_293_168, 0.),
+            Unit::InchesOfMercury => (Dimension::Pressure, 3.386_389, 0.),
+            Unit::Liters => (Dimension::Volume, 1., 0.),
+            Unit::Gallons => (Dimension::Volume, 3.785_411_784, 0.),
+            Unit::Radians => (Dimension::Angle, 1., 0.),
+            Unit::Degrees => (Dimension::Angle, PI / 180., 0.),
+            Unit::RadiansPerSecond => (Dimension::AngularVelocity, 1., 0.),
+            Unit::DegreesPerSecond => (Dimension::AngularVelocity, PI / 180., 0.),
+            Unit::Millimeters => (Dimension::Distance, 0.001, 0.),
+            Unit::Meters => (Dimension::Distance, 1., 0.),
+            Unit::Kilometers => (Dimension::Distance, 1000., 0.),
+            Unit::Feet => (Dimension::Distance, 0.3048, 0.),
+            Unit::Miles => (Dimension::Distance, 1609.344, 0.),
+            Unit::Kilograms => (Dimension::Mass, 1., 0.),
+            Unit::Pounds => (Dimension::Mass, 0.453_592_37, 0.),
+            Unit::Seconds => (Dimension::Time, 1., 0.),
+            Unit::Percent => (Dimension::Ratio, 1., 0.),
+            Unit::RevsPerMinute => (Dimension::RotationRate, 1., 0.),
+            Unit::Volts => (Dimension::Voltage, 1., 0.),
+            Unit::Newtons => (Dimension::Force, 1., 0.),
+            Unit::NewtonMeters => (Dimension::Torque, 1., 0.),
+            Unit::MetersPerSecondSquared => (Dimension::Acceleration, 1., 0.),
+            Unit::StandardGravity => (Dimension::Acceleration, 9.80665, 0.),
+            Unit::KilogramsPerHour => (Dimension::MassFlow, 1., 0.),
+            Unit::PoundsPerHour => (Dimension::MassFlow, 0.453_592_37, 0.),
+        }
+    }
+
+    /// Returns true if values in this unit convert to `unit`.
+    pub fn converts_to(&self, unit: Unit) -> bool {
+        self.definition().0 == unit.definition().0
+    }
+
+    /// Converts `value` from this unit to `unit`.  Returns `None` if the units measure different things, like a
+    /// speed and a temperature.
+    pub fn convert(&self, value: f64, unit: Unit) -> Option<f64> {
+        let (dimension, scale, offset) = self.definition();
+        let (to_dimension, to_scale, to_offset) = unit.definition();
+        if dimension != to_dimension {
+            return None;
+        }
+        Some((value * scale + offset - to_offset) / to_scale)
+    }
+}
+
+impl Display for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+impl FromStr for Unit {
+    type Err = IrError;
+
+    fn from_str(unit: &str) -> Result<Self, Self::Err> {
+        Unit::parse(unit).ok_or(IrError::TypeError)
+    }
+}
+
+/// A value with its unit, read with `IrData::get_quantity` or from a `Measurement`.
+/// ```
+/// use ir_telemetry::units::{Quantity, Unit};
+///
+/// let speed = Quantity::new(50., Unit::MetersPerSecond);
+/// assert_eq!(speed.to(Unit::KilometersPerHour).unwrap().value.round(), 180.);
+/// assert!(speed.to(Unit::Celsius).is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Quantity {
+    pub value: f64,
+    pub unit: Unit,
+}
+
+impl Quantity {
+    pub fn new(value: f64, unit: Unit) -> Self {
+        Self { value, unit }
+    }
+
+    /// Converts to `unit`.  Returns `None` if the units measure different things.
+    pub fn to(&self, unit: Unit) -> Option<Quantity> {
+        self.unit
+            .convert(self.value, unit)
+            .map(|value| Quantity { value, unit })
+    }
+}
+
+impl Display for Quantity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.value, self.unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(value: f64, from: &str, to: Unit) -> f64 {
+        Unit::parse(from).unwrap().convert(value, to).unwrap()
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert!((convert(10., "m/s", Unit::KilometersPerHour) - 36.).abs() < 1e-9);
+        assert!((convert(10., "m/s at 360 Hz", Unit::MilesPerHour) - 22.369_362_9).abs() < 1e-6);
+        assert!((convert(100., "C", Unit::Fahrenheit) - 212.).abs() < 1e-9);
+        assert!((convert(-40., "F", Unit::Celsius) + 40.).abs() < 1e-9);
+        assert!((convert(100., "kPa", Unit::Psi) - 14.503_773_8).abs() < 1e-6);
+        assert!((convert(3.785_411_784, "l", Unit::Gallons) - 1.).abs() < 1e-9);
+        assert!((convert(std::f64::consts::PI, "rad", Unit::Degrees) - 180.).abs() < 1e-9);
+        assert_eq!(Unit::Celsius.convert(1., Unit::Liters), None);
+        assert_eq!(Unit::parse("irsdk_Flags"), None);
+        assert_eq!("kph".parse::<Unit>().unwrap(), Unit::KilometersPerHour);
+        assert_eq!(Unit::KilometersPerHour.to_string(), "km/h");
+    }
+
+    #[test]
+    fn test_catalog_units() {
+        assert_eq!(Unit::parse("N*m"), Some(Unit::NewtonMeters));
+        assert_eq!(Unit::parse("Nm"), Some(Unit::NewtonMeters));
+        assert_eq!(Unit::NewtonMeters.to_string(), "N*m");
+        assert!((convert(9.80665, "m/s^2", Unit::StandardGravity) - 1.).abs() < 1e-9);
+        assert!((convert(10., "kg/h", Unit::PoundsPerHour) - 22.046_226_2).abs() < 1e-6);
+        assert_eq!(Unit::parse("kg/m^3"), None);
+    }
+}
@@ -5,6 +5,12 @@ pub(crate) fn byte_array_to_rust_string(array: &[u8]) -> String {
     )
 }
 
+/// A path in the temp directory that is unique to the test process, so tests running in parallel do not share files.
+#[cfg(test)]
+pub(crate) fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("ir_telemetry_{}_{}", std::process::id(), name))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
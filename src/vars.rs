@@ -0,0 +1,1865 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use crate::error::{IrError, Result};
+use crate::handle::VarHandle;
+use crate::mapped_file::values::DataVarType;
+use crate::mapped_file::var_header::VarHeader;
+use crate::telemetry::OptionalField;
+use crate::types::*;
+
+/// A Rust type a telemetry variable can be read as.
+pub trait VarValue: TryFrom<IrValue, Error = IrError> {
+    /// Returns true if a variable of `var_type` can be read as this type.
+    fn accepts(var_type: DataVarType) -> bool;
+
+    /// Reads an entry of a variable of `var_type` from its bytes, without going through `IrValue`.  `var_type` is one
+    /// the type accepts and `bytes` holds exactly one entry.
+    fn decode(var_type: DataVarType, bytes: &[u8]) -> Result<Self>
+    where
+        Self: Sized;
+}
+
+// The callers slice exactly one entry of the variable type.
+fn int(var_type: DataVarType, bytes: &[u8]) -> i32 {
+    match var_type {
+        DataVarType::Char => bytes[0] as i32,
+        _ => i32::from_le_bytes(bytes.try_into().unwrap()),
+    }
+}
+
+impl VarValue for u8 {
+    fn accepts(var_type: DataVarType) -> bool {
+        var_type == DataVarType::Char
+    }
+
+    fn decode(_: DataVarType, bytes: &[u8]) -> Result<Self> {
+        Ok(bytes[0])
+    }
+}
+
+impl VarValue for bool {
+    fn accepts(var_type: DataVarType) -> bool {
+        var_type == DataVarType::Bool
+    }
+
+    fn decode(_: DataVarType, bytes: &[u8]) -> Result<Self> {
+        Ok(bytes[0] != 0)
+    }
+}
+
+impl VarValue for i32 {
+    fn accepts(var_type: DataVarType) -> bool {
+        matches!(
+            var_type,
+            DataVarType::Char | DataVarType::Int | DataVarType::BitField
+        )
+    }
+
+    fn decode(var_type: DataVarType, bytes: &[u8]) -> Result<Self> {
+        Ok(int(var_type, bytes))
+    }
+}
+
+impl VarValue for f32 {
+    fn accepts(var_type: DataVarType) -> bool {
+        var_type == DataVarType::Float
+    }
+
+    fn decode(_: DataVarType, bytes: &[u8]) -> Result<Self> {
+        Ok(f32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+}
+
+impl VarValue for f64 {
+    fn accepts(var_type: DataVarType) -> bool {
+        matches!(var_type, DataVarType::Float | DataVarType::Double)
+    }
+
+    fn decode(var_type: DataVarType, bytes: &[u8]) -> Result<Self> {
+        Ok(match var_type {
+            DataVarType::Float => f32::from_le_bytes(bytes.try_into().unwrap()) as f64,
+            _ => f64::from_le_bytes(bytes.try_into().unwrap()),
+        })
+    }
+}
+
+/// Fails for negative times, like the `-1` iRacing writes for lap times that are not set.
+impl VarValue for Duration {
+    fn accepts(var_type: DataVarType) -> bool {
+        matches!(var_type, DataVarType::Float | DataVarType::Double)
+    }
+
+    fn decode(var_type: DataVarType, bytes: &[u8]) -> Result<Self> {
+        Duration::try_from_secs_f64(f64::decode(var_type, bytes)?)
+            .map_err(|_| IrError::ExceedsVariableRange)
+    }
+}
+
+/// `None` for the `-1` "no time" value and other negative or invalid times.
+impl VarValue for Option<Duration> {
+    fn accepts(var_type: DataVarType) -> bool {
+        Duration::accepts(var_type)
+    }
+
+    fn decode(var_type: DataVarType, bytes: &[u8]) -> Result<Self> {
+        Ok(Duration::try_from_secs_f64(f64::decode(var_type, bytes)?).ok())
+    }
+}
+
+macro_rules! impl_var_value_enum {
+    ($($type:ty),*) => {
+        $(
+            impl VarValue for $type {
+                fn accepts(var_type: DataVarType) -> bool {
+                    matches!(var_type, DataVarType::Int | DataVarType::BitField)
+                }
+
+                fn decode(var_type: DataVarType, bytes: &[u8]) -> Result<Self> {
+                    Ok(<$type>::from_code(int(var_type, bytes)))
+                }
+            }
+
+            impl OptionalField for $type {}
+        )*
+    };
+}
+
+macro_rules! impl_var_value_bitflags {
+    ($($type:ty),*) => {
+        $(
+            impl VarValue for $type {
+                fn accepts(var_type: DataVarType) -> bool {
+                    matches!(var_type, DataVarType::Int | DataVarType::BitField)
+                }
+
+                fn decode(var_type: DataVarType, bytes: &[u8]) -> Result<Self> {
+                    Ok(Self::from_bits_retain(int(var_type, bytes) as u32))
+                }
+            }
+
+            impl OptionalField for $type {}
+        )*
+    };
+}
+
+impl_var_value_enum!(
+    TrackLocation,
+    TrackSurface,
+    SessionState,
+    CarLeftRight,
+    PitStatus,
+    PaceMode,
+    TrackWetness,
+    IncidentFlags
+);
+
+impl_var_value_bitflags!(
+    EngineWarnings,
+    Flags,
+    CameraState,
+    PitServiceFlags,
+    PaceFlags
+);
+
+/// A typed key of a telemetry variable, like `vars::SPEED`.  Read with `IrData::read`.
+pub trait Key {
+    /// The type the variable is read as.
+    type Value: TryFrom<IrValue, Error = IrError>;
+
+    /// The name of the variable in the telemetry.
+    fn name(&self) -> &'static str;
+
+    /// Returns true if the variable described by `header` has the type and count of the key.
+    fn matches(&self, header: &VarHeader) -> bool;
+
+    /// Finds the variable in the variable headers of the session and checks its type.
+    fn resolve<'a>(&self, var_headers: &'a HashMap<String, VarHeader>) -> Option<&'a VarHeader> {
+        var_headers
+            .get(self.name())
+            .filter(|header| self.matches(header))
+    }
+}
+
+macro_rules! key {
+    ($(#[$doc:meta])* $key:ident) => {
+        $(#[$doc])*
+        pub struct $key<T> {
+            name: &'static str,
+            unit: &'static str,
+            desc: &'static str,
+            value: PhantomData<fn() -> T>,
+        }
+
+        impl<T> $key<T> {
+            pub const fn new(name: &'static str, unit: &'static str, desc: &'static str) -> Self {
+                Self {
+                    name,
+                    unit,
+                    desc,
+                    value: PhantomData,
+                }
+            }
+
+            /// The name of the variable in the telemetry.
+            pub const fn name(&self) -> &'static str {
+                self.name
+            }
+
+            /// The unit as documented by iRacing, like `m/s`.  Empty if the variable has no unit.
+            pub const fn unit(&self) -> &'static str {
+                self.unit
+            }
+
+            /// The description as documented by iRacing.
+            pub const fn desc(&self) -> &'static str {
+                self.desc
+            }
+        }
+
+        impl<T: VarValue> $key<T> {
+            /// Resolves the variable in the variable headers of a session for reads without allocations.
+            pub fn handle(&self, var_headers: &HashMap<String, VarHeader>) -> Result<VarHandle<T>> {
+                VarHandle::resolve(var_headers, self.name)
+            }
+        }
+
+        impl<T> Clone for $key<T> {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl<T> Copy for $key<T> {}
+
+        impl<T> std::fmt::Debug for $key<T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(stringify!($key))
+                    .field("name", &self.name)
+                    .field("unit", &self.unit)
+                    .field("type", &std::any::type_name::<T>())
+                    .finish()
+            }
+        }
+    };
+}
+
+key!(
+    /// A variable with a single value.
+    Var
+);
+
+key!(
+    /// A variable with an entry per car index, or per sample for the 360 Hz `_ST` variables.
+    VarArray
+);
+
+impl<T: VarValue> Key for Var<T> {
+    type Value = T;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn matches(&self, header: &VarHeader) -> bool {
+        header.count == 1 && T::accepts(header._type)
+    }
+}
+
+impl<T: VarValue> Key for VarArray<T> {
+    type Value = Vec<T>;
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn matches(&self, header: &VarHeader) -> bool {
+        header.count > 1 && T::accepts(header._type)
+    }
+}
+
+// The variables documented by iRacing.  Variables that depend on the car, like shock deflections, are left out.
+// Lap times are read as `Option<Duration>`, `None` until set.  Session times stay `f64` seconds, like
+// `IrData::session_time`, and signed times, like the deltas, `f32`.
+
+/// Seconds since session start.
+pub const SESSION_TIME: Var<f64> = Var::new("SessionTime", "s", "Seconds since session start");
+
+/// Current update number.
+pub const SESSION_TICK: Var<i32> = Var::new("SessionTick", "", "Current update number");
+
+/// Session number.
+pub const SESSION_NUM: Var<i32> = Var::new("SessionNum", "", "Session number");
+
+/// Session state.
+pub const SESSION_STATE: Var<SessionState> =
+    Var::new("SessionState", "irsdk_SessionState", "Session state");
+
+/// Session ID.
+pub const SESSION_UNIQUE_ID: Var<i32> = Var::new("SessionUniqueID", "", "Session ID");
+
+/// Session flags.
+pub const SESSION_FLAGS: Var<Flags> = Var::new("SessionFlags", "irsdk_Flags", "Session flags");
+
+/// Seconds left till session ends.
+pub const SESSION_TIME_REMAIN: Var<f64> =
+    Var::new("SessionTimeRemain", "s", "Seconds left till session ends");
+
+/// Old laps left till session ends use SessionLapsRemainEx.
+pub const SESSION_LAPS_REMAIN: Var<i32> = Var::new(
+    "SessionLapsRemain",
+    "",
+    "Old laps left till session ends use SessionLapsRemainEx",
+);
+
+/// New improved laps left till session ends.
+pub const SESSION_LAPS_REMAIN_EX: Var<i32> = Var::new(
+    "SessionLapsRemainEx",
+    "",
+    "New improved laps left till session ends",
+);
+
+/// Total number of seconds in session.
+pub const SESSION_TIME_TOTAL: Var<f64> = Var::new(
+    "SessionTimeTotal",
+    "s",
+    "Total number of seconds in session",
+);
+
+/// Total number of laps in session.
+pub const SESSION_LAPS_TOTAL: Var<i32> =
+    Var::new("SessionLapsTotal", "", "Total number of laps in session");
+
+/// Joker laps remaining to be taken.
+pub const SESSION_JOKER_LAPS_REMAIN: Var<i32> = Var::new(
+    "SessionJokerLapsRemain",
+    "",
+    "Joker laps remaining to be taken",
+);
+
+/// Player is currently completing a joker lap.
+pub const SESSION_ON_JOKER_LAP: Var<bool> = Var::new(
+    "SessionOnJokerLap",
+    "",
+    "Player is currently completing a joker lap",
+);
+
+/// Time of day in seconds.
+pub const SESSION_TIME_OF_DAY: Var<f32> =
+    Var::new("SessionTimeOfDay", "s", "Time of day in seconds");
+
+/// The car index of the current person speaking on the radio.
+pub const RADIO_TRANSMIT_CAR_IDX: Var<i32> = Var::new(
+    "RadioTransmitCarIdx",
+    "",
+    "The car index of the current person speaking on the radio",
+);
+
+/// The radio index of the current person speaking on the radio.
+pub const RADIO_TRANSMIT_RADIO_IDX: Var<i32> = Var::new(
+    "RadioTransmitRadioIdx",
+    "",
+    "The radio index of the current person speaking on the radio",
+);
+
+/// The frequency index of the current person speaking on the radio.
+pub const RADIO_TRANSMIT_FREQUENCY_IDX: Var<i32> = Var::new(
+    "RadioTransmitFrequencyIdx",
+    "",
+    "The frequency index of the current person speaking on the radio",
+);
+
+/// Default units for the user interface 0 = english 1 = metric.
+pub const DISPLAY_UNITS: Var<i32> = Var::new(
+    "DisplayUnits",
+    "",
+    "Default units for the user interface 0 = english 1 = metric",
+);
+
+/// Driver activated flag.
+pub const DRIVER_MARKER: Var<bool> = Var::new("DriverMarker", "", "Driver activated flag");
+
+/// Push to talk button state.
+pub const PUSH_TO_TALK: Var<bool> = Var::new("PushToTalk", "", "Push to talk button state");
+
+/// Push to pass button state.
+pub const PUSH_TO_PASS: Var<bool> = Var::new("PushToPass", "", "Push to pass button state");
+
+/// Hybrid manual boost state.
+pub const MANUAL_BOOST: Var<bool> = Var::new("ManualBoost", "", "Hybrid manual boost state");
+
+/// Hybrid manual no boost state.
+pub const MANUAL_NO_BOOST: Var<bool> =
+    Var::new("ManualNoBoost", "", "Hybrid manual no boost state");
+
+/// 1=Car on track physics running with player in car.
+pub const IS_ON_TRACK: Var<bool> = Var::new(
+    "IsOnTrack",
+    "",
+    "1=Car on track physics running with player in car",
+);
+
+/// 0=replay not playing, 1=replay playing.
+pub const IS_REPLAY_PLAYING: Var<bool> = Var::new(
+    "IsReplayPlaying",
+    "",
+    "0=replay not playing, 1=replay playing",
+);
+
+/// Integer replay frame number (60 per second).
+pub const REPLAY_FRAME_NUM: Var<i32> = Var::new(
+    "ReplayFrameNum",
+    "",
+    "Integer replay frame number (60 per second)",
+);
+
+/// Integer replay frame number from end of tape.
+pub const REPLAY_FRAME_NUM_END: Var<i32> = Var::new(
+    "ReplayFrameNumEnd",
+    "",
+    "Integer replay frame number from end of tape",
+);
+
+/// 0=disk based telemetry turned off, 1=turned on.
+pub const IS_DISK_LOGGING_ENABLED: Var<bool> = Var::new(
+    "IsDiskLoggingEnabled",
+    "",
+    "0=disk based telemetry turned off, 1=turned on",
+);
+
+/// 0=disk based telemetry file not being written, 1=being written.
+pub const IS_DISK_LOGGING_ACTIVE: Var<bool> = Var::new(
+    "IsDiskLoggingActive",
+    "",
+    "0=disk based telemetry file not being written, 1=being written",
+);
+
+/// Average frames per second.
+pub const FRAME_RATE: Var<f32> = Var::new("FrameRate", "fps", "Average frames per second");
+
+/// Percent of available tim fg thread took with a 1 sec avg.
+pub const CPU_USAGE_FG: Var<f32> = Var::new(
+    "CpuUsageFG",
+    "%",
+    "Percent of available tim fg thread took with a 1 sec avg",
+);
+
+/// Percent of available tim gpu took with a 1 sec avg.
+pub const GPU_USAGE: Var<f32> = Var::new(
+    "GpuUsage",
+    "%",
+    "Percent of available tim gpu took with a 1 sec avg",
+);
+
+/// Communications average latency.
+pub const CHAN_AVG_LATENCY: Var<f32> =
+    Var::new("ChanAvgLatency", "s", "Communications average latency");
+
+/// Communications latency.
+pub const CHAN_LATENCY: Var<f32> = Var::new("ChanLatency", "s", "Communications latency");
+
+/// Communications quality.
+pub const CHAN_QUALITY: Var<f32> = Var::new("ChanQuality", "%", "Communications quality");
+
+/// Partner communications quality.
+pub const CHAN_PARTNER_QUALITY: Var<f32> =
+    Var::new("ChanPartnerQuality", "%", "Partner communications quality");
+
+/// Percent of available tim bg thread took with a 1 sec avg.
+pub const CPU_USAGE_BG: Var<f32> = Var::new(
+    "CpuUsageBG",
+    "%",
+    "Percent of available tim bg thread took with a 1 sec avg",
+);
+
+/// Communications server clock skew.
+pub const CHAN_CLOCK_SKEW: Var<f32> =
+    Var::new("ChanClockSkew", "s", "Communications server clock skew");
+
+/// Memory page faults per second.
+pub const MEM_PAGE_FAULT_SEC: Var<f32> =
+    Var::new("MemPageFaultSec", "", "Memory page faults per second");
+
+/// Memory soft page faults per second.
+pub const MEM_SOFT_PAGE_FAULT_SEC: Var<f32> = Var::new(
+    "MemSoftPageFaultSec",
+    "",
+    "Memory soft page faults per second",
+);
+
+/// Players position in race.
+pub const PLAYER_CAR_POSITION: Var<i32> =
+    Var::new("PlayerCarPosition", "", "Players position in race");
+
+/// Players class position in race.
+pub const PLAYER_CAR_CLASS_POSITION: Var<i32> = Var::new(
+    "PlayerCarClassPosition",
+    "",
+    "Players class position in race",
+);
+
+/// Player car class id.
+pub const PLAYER_CAR_CLASS: Var<i32> = Var::new("PlayerCarClass", "", "Player car class id");
+
+/// Players car track surface type.
+pub const PLAYER_TRACK_SURFACE: Var<TrackLocation> = Var::new(
+    "PlayerTrackSurface",
+    "irsdk_TrkLoc",
+    "Players car track surface type",
+);
+
+/// Players car track surface material type.
+pub const PLAYER_TRACK_SURFACE_MATERIAL: Var<TrackSurface> = Var::new(
+    "PlayerTrackSurfaceMaterial",
+    "irsdk_TrkSurf",
+    "Players car track surface material type",
+);
+
+/// Players carIdx.
+pub const PLAYER_CAR_IDX: Var<i32> = Var::new("PlayerCarIdx", "", "Players carIdx");
+
+/// Players team incident count for this session.
+pub const PLAYER_CAR_TEAM_INCIDENT_COUNT: Var<i32> = Var::new(
+    "PlayerCarTeamIncidentCount",
+    "",
+    "Players team incident count for this session",
+);
+
+/// Players own incident count for this session.
+pub const PLAYER_CAR_MY_INCIDENT_COUNT: Var<i32> = Var::new(
+    "PlayerCarMyIncidentCount",
+    "",
+    "Players own incident count for this session",
+);
+
+/// Teams current drivers incident count for this session.
+pub const PLAYER_CAR_DRIVER_INCIDENT_COUNT: Var<i32> = Var::new(
+    "PlayerCarDriverIncidentCount",
+    "",
+    "Teams current drivers incident count for this session",
+);
+
+/// Players last incident, report and penalty.
+pub const PLAYER_INCIDENTS: Var<IncidentFlags> = Var::new(
+    "PlayerIncidents",
+    "irsdk_IncidentFlags",
+    "Players last incident, report and penalty",
+);
+
+/// Players weight penalty.
+pub const PLAYER_CAR_WEIGHT_PENALTY: Var<f32> =
+    Var::new("PlayerCarWeightPenalty", "kg", "Players weight penalty");
+
+/// Players power adjust.
+pub const PLAYER_CAR_POWER_ADJUST: Var<f32> =
+    Var::new("PlayerCarPowerAdjust", "%", "Players power adjust");
+
+/// Players dry tire set limit.
+pub const PLAYER_CAR_DRY_TIRE_SET_LIMIT: Var<i32> =
+    Var::new("PlayerCarDryTireSetLimit", "", "Players dry tire set limit");
+
+/// Players car is being towed if time is greater than zero.
+pub const PLAYER_CAR_TOW_TIME: Var<f32> = Var::new(
+    "PlayerCarTowTime",
+    "s",
+    "Players car is being towed if time is greater than zero",
+);
+
+/// Players car is properly in their pitstall.
+pub const PLAYER_CAR_IN_PIT_STALL: Var<bool> = Var::new(
+    "PlayerCarInPitStall",
+    "",
+    "Players car is properly in their pitstall",
+);
+
+/// Players car pit service status bits.
+pub const PLAYER_CAR_PIT_SV_STATUS: Var<PitStatus> = Var::new(
+    "PlayerCarPitSvStatus",
+    "irsdk_PitSvStatus",
+    "Players car pit service status bits",
+);
+
+/// Players car current tire compound.
+pub const PLAYER_TIRE_COMPOUND: Var<i32> = Var::new(
+    "PlayerTireCompound",
+    "",
+    "Players car current tire compound",
+);
+
+/// Players car number of fast repairs used.
+pub const PLAYER_FAST_REPAIRS_USED: Var<i32> = Var::new(
+    "PlayerFastRepairsUsed",
+    "",
+    "Players car number of fast repairs used",
+);
+
+/// Laps started by car index.
+pub const CAR_IDX_LAP: VarArray<i32> = VarArray::new("CarIdxLap", "", "Laps started by car index");
+
+/// Laps completed by car index.
+pub const CAR_IDX_LAP_COMPLETED: VarArray<i32> =
+    VarArray::new("CarIdxLapCompleted", "", "Laps completed by car index");
+
+/// Percentage distance around lap by car index.
+pub const CAR_IDX_LAP_DIST_PCT: VarArray<f32> = VarArray::new(
+    "CarIdxLapDistPct",
+    "%",
+    "Percentage distance around lap by car index",
+);
+
+/// Track surface type by car index.
+pub const CAR_IDX_TRACK_SURFACE: VarArray<TrackLocation> = VarArray::new(
+    "CarIdxTrackSurface",
+    "irsdk_TrkLoc",
+    "Track surface type by car index",
+);
+
+/// Track surface material type by car index.
+pub const CAR_IDX_TRACK_SURFACE_MATERIAL: VarArray<TrackSurface> = VarArray::new(
+    "CarIdxTrackSurfaceMaterial",
+    "irsdk_TrkSurf",
+    "Track surface material type by car index",
+);
+
+/// On pit road between the cones by car index.
+pub const CAR_IDX_ON_PIT_ROAD: VarArray<bool> = VarArray::new(
+    "CarIdxOnPitRoad",
+    "",
+    "On pit road between the cones by car index",
+);
+
+/// Cars position in race by car index.
+pub const CAR_IDX_POSITION: VarArray<i32> =
+    VarArray::new("CarIdxPosition", "", "Cars position in race by car index");
+
+/// Cars class position in race by car index.
+pub const CAR_IDX_CLASS_POSITION: VarArray<i32> = VarArray::new(
+    "CarIdxClassPosition",
+    "",
+    "Cars class position in race by car index",
+);
+
+/// Cars class id by car index.
+pub const CAR_IDX_CLASS: VarArray<i32> =
+    VarArray::new("CarIdxClass", "", "Cars class id by car index");
+
+/// Race time behind leader or fastest lap time otherwise.
+pub const CAR_IDX_F2_TIME: VarArray<f32> = VarArray::new(
+    "CarIdxF2Time",
+    "s",
+    "Race time behind leader or fastest lap time otherwise",
+);
+
+/// Estimated time to reach current location on track.
+pub const CAR_IDX_EST_TIME: VarArray<f32> = VarArray::new(
+    "CarIdxEstTime",
+    "s",
+    "Estimated time to reach current location on track",
+);
+
+/// Cars last lap time.
+pub const CAR_IDX_LAST_LAP_TIME: VarArray<Option<Duration>> =
+    VarArray::new("CarIdxLastLapTime", "s", "Cars last lap time");
+
+/// Cars best lap time.
+pub const CAR_IDX_BEST_LAP_TIME: VarArray<Option<Duration>> =
+    VarArray::new("CarIdxBestLapTime", "s", "Cars best lap time");
+
+/// Cars best lap number.
+pub const CAR_IDX_BEST_LAP_NUM: VarArray<i32> =
+    VarArray::new("CarIdxBestLapNum", "", "Cars best lap number");
+
+/// Cars current tire compound.
+pub const CAR_IDX_TIRE_COMPOUND: VarArray<i32> =
+    VarArray::new("CarIdxTireCompound", "", "Cars current tire compound");
+
+/// Cars Qual tire compound.
+pub const CAR_IDX_QUAL_TIRE_COMPOUND: VarArray<i32> =
+    VarArray::new("CarIdxQualTireCompound", "", "Cars Qual tire compound");
+
+/// Cars Qual tire compound is locked-in.
+pub const CAR_IDX_QUAL_TIRE_COMPOUND_LOCKED: VarArray<bool> = VarArray::new(
+    "CarIdxQualTireCompoundLocked",
+    "",
+    "Cars Qual tire compound is locked-in",
+);
+
+/// How many fast repairs each car has used.
+pub const CAR_IDX_FAST_REPAIRS_USED: VarArray<i32> = VarArray::new(
+    "CarIdxFastRepairsUsed",
+    "",
+    "How many fast repairs each car has used",
+);
+
+/// Session flags for each player.
+pub const CAR_IDX_SESSION_FLAGS: VarArray<Flags> = VarArray::new(
+    "CarIdxSessionFlags",
+    "irsdk_Flags",
+    "Session flags for each player",
+);
+
+/// Are we pacing or not.
+pub const PACE_MODE: Var<PaceMode> = Var::new("PaceMode", "irsdk_PaceMode", "Are we pacing or not");
+
+/// What line cars are pacing in, or -1 if not pacing.
+pub const CAR_IDX_PACE_LINE: VarArray<i32> = VarArray::new(
+    "CarIdxPaceLine",
+    "",
+    "What line cars are pacing in, or -1 if not pacing",
+);
+
+/// What row cars are pacing in, or -1 if not pacing.
+pub const CAR_IDX_PACE_ROW: VarArray<i32> = VarArray::new(
+    "CarIdxPaceRow",
+    "",
+    "What row cars are pacing in, or -1 if not pacing",
+);
+
+/// Pacing status flags for each car.
+pub const CAR_IDX_PACE_FLAGS: VarArray<PaceFlags> = VarArray::new(
+    "CarIdxPaceFlags",
+    "irsdk_PaceFlags",
+    "Pacing status flags for each car",
+);
+
+/// Is the player car on pit road between the cones.
+pub const ON_PIT_ROAD: Var<bool> = Var::new(
+    "OnPitRoad",
+    "",
+    "Is the player car on pit road between the cones",
+);
+
+/// Steering wheel angle by car index.
+pub const CAR_IDX_STEER: VarArray<f32> =
+    VarArray::new("CarIdxSteer", "rad", "Steering wheel angle by car index");
+
+/// Engine rpm by car index.
+pub const CAR_IDX_RPM: VarArray<f32> =
+    VarArray::new("CarIdxRPM", "revs/min", "Engine rpm by car index");
+
+/// -1=reverse, 0=neutral, 1..n=current gear by car index.
+pub const CAR_IDX_GEAR: VarArray<i32> = VarArray::new(
+    "CarIdxGear",
+    "",
+    "-1=reverse, 0=neutral, 1..n=current gear by car index",
+);
+
+/// Steering wheel angle.
+pub const STEERING_WHEEL_ANGLE: Var<f32> =
+    Var::new("SteeringWheelAngle", "rad", "Steering wheel angle");
+
+/// 0=off throttle to 1=full throttle.
+pub const THROTTLE: Var<f32> = Var::new("Throttle", "%", "0=off throttle to 1=full throttle");
+
+/// 0=brake released to 1=max pedal force.
+pub const BRAKE: Var<f32> = Var::new("Brake", "%", "0=brake released to 1=max pedal force");
+
+/// 0=disengaged to 1=fully engaged.
+pub const CLUTCH: Var<f32> = Var::new("Clutch", "%", "0=disengaged to 1=fully engaged");
+
+/// -1=reverse, 0=neutral, 1..n=current gear.
+pub const GEAR: Var<i32> = Var::new("Gear", "", "-1=reverse, 0=neutral, 1..n=current gear");
+
+/// Engine rpm.
+pub const RPM: Var<f32> = Var::new("RPM", "revs/min", "Engine rpm");
+
+/// Shift light first light rpm.
+pub const PLAYER_CAR_SL_FIRST_RPM: Var<f32> = Var::new(
+    "PlayerCarSLFirstRPM",
+    "revs/min",
+    "Shift light first light rpm",
+);
+
+/// Shift light shift rpm.
+pub const PLAYER_CAR_SL_SHIFT_RPM: Var<f32> =
+    Var::new("PlayerCarSLShiftRPM", "revs/min", "Shift light shift rpm");
+
+/// Shift light last light rpm.
+pub const PLAYER_CAR_SL_LAST_RPM: Var<f32> = Var::new(
+    "PlayerCarSLLastRPM",
+    "revs/min",
+    "Shift light last light rpm",
+);
+
+/// Shift light blink rpm.
+pub const PLAYER_CAR_SL_BLINK_RPM: Var<f32> =
+    Var::new("PlayerCarSLBlinkRPM", "revs/min", "Shift light blink rpm");
+
+/// Laps started count.
+pub const LAP: Var<i32> = Var::new("Lap", "", "Laps started count");
+
+/// Laps completed count.
+pub const LAP_COMPLETED: Var<i32> = Var::new("LapCompleted", "", "Laps completed count");
+
+/// Meters traveled from S/F this lap.
+pub const LAP_DIST: Var<f32> = Var::new("LapDist", "m", "Meters traveled from S/F this lap");
+
+/// Percentage distance around lap.
+pub const LAP_DIST_PCT: Var<f32> = Var::new("LapDistPct", "%", "Percentage distance around lap");
+
+/// Laps completed in race.
+pub const RACE_LAPS: Var<i32> = Var::new("RaceLaps", "", "Laps completed in race");
+
+/// Players best lap number.
+pub const LAP_BEST_LAP: Var<i32> = Var::new("LapBestLap", "", "Players best lap number");
+
+/// Players best lap time.
+pub const LAP_BEST_LAP_TIME: Var<Option<Duration>> =
+    Var::new("LapBestLapTime", "s", "Players best lap time");
+
+/// Players last lap time.
+pub const LAP_LAST_LAP_TIME: Var<Option<Duration>> =
+    Var::new("LapLastLapTime", "s", "Players last lap time");
+
+/// Estimate of players current lap time as shown in F3 box.
+pub const LAP_CURRENT_LAP_TIME: Var<Duration> = Var::new(
+    "LapCurrentLapTime",
+    "s",
+    "Estimate of players current lap time as shown in F3 box",
+);
+
+/// Player num consecutive clean laps completed for N average.
+pub const LAP_LAS_N_LAP_SEQ: Var<i32> = Var::new(
+    "LapLasNLapSeq",
+    "",
+    "Player num consecutive clean laps completed for N average",
+);
+
+/// Player last N average lap time.
+pub const LAP_LAST_N_LAP_TIME: Var<f32> =
+    Var::new("LapLastNLapTime", "s", "Player last N average lap time");
+
+/// Player last lap in best N average lap time.
+pub const LAP_BEST_N_LAP_LAP: Var<i32> = Var::new(
+    "LapBestNLapLap",
+    "",
+    "Player last lap in best N average lap time",
+);
+
+/// Player best N average lap time.
+pub const LAP_BEST_N_LAP_TIME: Var<Option<Duration>> =
+    Var::new("LapBestNLapTime", "s", "Player best N average lap time");
+
+/// Delta time for best lap.
+pub const LAP_DELTA_TO_BEST_LAP: Var<f32> =
+    Var::new("LapDeltaToBestLap", "s", "Delta time for best lap");
+
+/// Rate of change of delta time for best lap.
+pub const LAP_DELTA_TO_BEST_LAP_DD: Var<f32> = Var::new(
+    "LapDeltaToBestLap_DD",
+    "s/s",
+    "Rate of change of delta time for best lap",
+);
+
+/// Delta time for best lap is valid.
+pub const LAP_DELTA_TO_BEST_LAP_OK: Var<bool> = Var::new(
+    "LapDeltaToBestLap_OK",
+    "",
+    "Delta time for best lap is valid",
+);
+
+/// Delta time for optimal lap.
+pub const LAP_DELTA_TO_OPTIMAL_LAP: Var<f32> =
+    Var::new("LapDeltaToOptimalLap", "s", "Delta time for optimal lap");
+
+/// Rate of change of delta time for optimal lap.
+pub const LAP_DELTA_TO_OPTIMAL_LAP_DD: Var<f32> = Var::new(
+    "LapDeltaToOptimalLap_DD",
+    "s/s",
+    "Rate of change of delta time for optimal lap",
+);
+
+/// Delta time for optimal lap is valid.
+pub const LAP_DELTA_TO_OPTIMAL_LAP_OK: Var<bool> = Var::new(
+    "LapDeltaToOptimalLap_OK",
+    "",
+    "Delta time for optimal lap is valid",
+);
+
+/// Delta time for session best lap.
+pub const LAP_DELTA_TO_SESSION_BEST_LAP: Var<f32> = Var::new(
+    "LapDeltaToSessionBestLap",
+    "s",
+    "Delta time for session best lap",
+);
+
+/// Rate of change of delta time for session best lap.
+pub const LAP_DELTA_TO_SESSION_BEST_LAP_DD: Var<f32> = Var::new(
+    "LapDeltaToSessionBestLap_DD",
+    "s/s",
+    "Rate of change of delta time for session best lap",
+);
+
+/// Delta time for session best lap is valid.
+pub const LAP_DELTA_TO_SESSION_BEST_LAP_OK: Var<bool> = Var::new(
+    "LapDeltaToSessionBestLap_OK",
+    "",
+    "Delta time for session best lap is valid",
+);
+
+/// Delta time for session optimal lap.
+pub const LAP_DELTA_TO_SESSION_OPTIMAL_LAP: Var<f32> = Var::new(
+    "LapDeltaToSessionOptimalLap",
+    "s",
+    "Delta time for session optimal lap",
+);
+
+/// Rate of change of delta time for session optimal lap.
+pub const LAP_DELTA_TO_SESSION_OPTIMAL_LAP_DD: Var<f32> = Var::new(
+    "LapDeltaToSessionOptimalLap_DD",
+    "s/s",
+    "Rate of change of delta time for session optimal lap",
+);
+
+/// Delta time for session optimal lap is valid.
+pub const LAP_DELTA_TO_SESSION_OPTIMAL_LAP_OK: Var<bool> = Var::new(
+    "LapDeltaToSessionOptimalLap_OK",
+    "",
+    "Delta time for session optimal lap is valid",
+);
+
+/// Delta time for session last lap.
+pub const LAP_DELTA_TO_SESSION_LASTL_LAP: Var<f32> = Var::new(
+    "LapDeltaToSessionLastlLap",
+    "s",
+    "Delta time for session last lap",
+);
+
+/// Rate of change of delta time for session last lap.
+pub const LAP_DELTA_TO_SESSION_LASTL_LAP_DD: Var<f32> = Var::new(
+    "LapDeltaToSessionLastlLap_DD",
+    "s/s",
+    "Rate of change of delta time for session last lap",
+);
+
+/// Delta time for session last lap is valid.
+pub const LAP_DELTA_TO_SESSION_LASTL_LAP_OK: Var<bool> = Var::new(
+    "LapDeltaToSessionLastlLap_OK",
+    "",
+    "Delta time for session last lap is valid",
+);
+
+/// GPS vehicle speed.
+pub const SPEED: Var<f32> = Var::new("Speed", "m/s", "GPS vehicle speed");
+
+/// Yaw orientation.
+pub const YAW: Var<f32> = Var::new("Yaw", "rad", "Yaw orientation");
+
+/// Yaw orientation relative to north.
+pub const YAW_NORTH: Var<f32> = Var::new("YawNorth", "rad", "Yaw orientation relative to north");
+
+/// Pitch orientation.
+pub const PITCH: Var<f32> = Var::new("Pitch", "rad", "Pitch orientation");
+
+/// Roll orientation.
+pub const ROLL: Var<f32> = Var::new("Roll", "rad", "Roll orientation");
+
+/// Indicate action the reset key will take 0 enter 1 exit 2 reset.
+pub const ENTER_EXIT_RESET: Var<i32> = Var::new(
+    "EnterExitReset",
+    "",
+    "Indicate action the reset key will take 0 enter 1 exit 2 reset",
+);
+
+/// Deprecated, set to TrackTempCrew.
+pub const TRACK_TEMP: Var<f32> = Var::new("TrackTemp", "C", "Deprecated, set to TrackTempCrew");
+
+/// Temperature of track measured by crew around track.
+pub const TRACK_TEMP_CREW: Var<f32> = Var::new(
+    "TrackTempCrew",
+    "C",
+    "Temperature of track measured by crew around track",
+);
+
+/// Temperature of air at start/finish line.
+pub const AIR_TEMP: Var<f32> = Var::new("AirTemp", "C", "Temperature of air at start/finish line");
+
+/// How wet is the average track surface.
+pub const TRACK_WETNESS: Var<TrackWetness> = Var::new(
+    "TrackWetness",
+    "irsdk_TrackWetness",
+    "How wet is the average track surface",
+);
+
+/// Skies (0=clear/1=p cloudy/2=m cloudy/3=overcast).
+pub const SKIES: Var<i32> = Var::new(
+    "Skies",
+    "",
+    "Skies (0=clear/1=p cloudy/2=m cloudy/3=overcast)",
+);
+
+/// Density of air at start/finish line.
+pub const AIR_DENSITY: Var<f32> = Var::new(
+    "AirDensity",
+    "kg/m^3",
+    "Density of air at start/finish line",
+);
+
+/// Pressure of air at start/finish line.
+pub const AIR_PRESSURE: Var<f32> =
+    Var::new("AirPressure", "Pa", "Pressure of air at start/finish line");
+
+/// Wind velocity at start/finish line.
+pub const WIND_VEL: Var<f32> = Var::new("WindVel", "m/s", "Wind velocity at start/finish line");
+
+/// Wind direction at start/finish line.
+pub const WIND_DIR: Var<f32> = Var::new("WindDir", "rad", "Wind direction at start/finish line");
+
+/// Relative Humidity at start/finish line.
+pub const RELATIVE_HUMIDITY: Var<f32> = Var::new(
+    "RelativeHumidity",
+    "%",
+    "Relative Humidity at start/finish line",
+);
+
+/// Fog level at start/finish line.
+pub const FOG_LEVEL: Var<f32> = Var::new("FogLevel", "%", "Fog level at start/finish line");
+
+/// Precipitation at start/finish line.
+pub const PRECIPITATION: Var<f32> =
+    Var::new("Precipitation", "%", "Precipitation at start/finish line");
+
+/// Sun angle above horizon in radians.
+pub const SOLAR_ALTITUDE: Var<f32> =
+    Var::new("SolarAltitude", "rad", "Sun angle above horizon in radians");
+
+/// Sun angle clockwise from north in radians.
+pub const SOLAR_AZIMUTH: Var<f32> = Var::new(
+    "SolarAzimuth",
+    "rad",
+    "Sun angle clockwise from north in radians",
+);
+
+/// The steward says rain tires can be used.
+pub const WEATHER_DECLARED_WET: Var<bool> = Var::new(
+    "WeatherDeclaredWet",
+    "",
+    "The steward says rain tires can be used",
+);
+
+/// Status of driver change lap requirements.
+pub const DC_LAP_STATUS: Var<i32> = Var::new(
+    "DCLapStatus",
+    "",
+    "Status of driver change lap requirements",
+);
+
+/// Number of team drivers who have run a stint.
+pub const DC_DRIVERS_SO_FAR: Var<i32> = Var::new(
+    "DCDriversSoFar",
+    "",
+    "Number of team drivers who have run a stint",
+);
+
+/// True if it is ok to reload car textures at this time.
+pub const OK_TO_RELOAD_TEXTURES: Var<bool> = Var::new(
+    "OkToReloadTextures",
+    "",
+    "True if it is ok to reload car textures at this time",
+);
+
+/// True if the car_num texture will be loaded.
+pub const LOAD_NUM_TEXTURES: Var<bool> = Var::new(
+    "LoadNumTextures",
+    "",
+    "True if the car_num texture will be loaded",
+);
+
+/// Notify if car is to the left or right of driver.
+pub const CAR_LEFT_RIGHT: Var<CarLeftRight> = Var::new(
+    "CarLeftRight",
+    "irsdk_CarLeftRight",
+    "Notify if car is to the left or right of driver",
+);
+
+/// True if pit stop is allowed for the current player.
+pub const PITS_OPEN: Var<bool> = Var::new(
+    "PitsOpen",
+    "",
+    "True if pit stop is allowed for the current player",
+);
+
+/// True if video capture system is enabled.
+pub const VID_CAP_ENABLED: Var<bool> = Var::new(
+    "VidCapEnabled",
+    "",
+    "True if video capture system is enabled",
+);
+
+/// True if video currently being captured.
+pub const VID_CAP_ACTIVE: Var<bool> =
+    Var::new("VidCapActive", "", "True if video currently being captured");
+
+/// Time left for mandatory pit repairs if repairs are active.
+pub const PIT_REPAIR_LEFT: Var<f32> = Var::new(
+    "PitRepairLeft",
+    "s",
+    "Time left for mandatory pit repairs if repairs are active",
+);
+
+/// Time left for optional repairs if repairs are active.
+pub const PIT_OPT_REPAIR_LEFT: Var<f32> = Var::new(
+    "PitOptRepairLeft",
+    "s",
+    "Time left for optional repairs if repairs are active",
+);
+
+/// Is the player getting pit stop service.
+pub const PITSTOP_ACTIVE: Var<bool> = Var::new(
+    "PitstopActive",
+    "",
+    "Is the player getting pit stop service",
+);
+
+/// How many fast repairs used so far.
+pub const FAST_REPAIR_USED: Var<i32> =
+    Var::new("FastRepairUsed", "", "How many fast repairs used so far");
+
+/// How many fast repairs left, 255 is unlimited.
+pub const FAST_REPAIR_AVAILABLE: Var<i32> = Var::new(
+    "FastRepairAvailable",
+    "",
+    "How many fast repairs left, 255 is unlimited",
+);
+
+/// How many left front tires used so far.
+pub const LF_TIRES_USED: Var<i32> =
+    Var::new("LFTiresUsed", "", "How many left front tires used so far");
+
+/// How many right front tires used so far.
+pub const RF_TIRES_USED: Var<i32> =
+    Var::new("RFTiresUsed", "", "How many right front tires used so far");
+
+/// How many left rear tires used so far.
+pub const LR_TIRES_USED: Var<i32> =
+    Var::new("LRTiresUsed", "", "How many left rear tires used so far");
+
+/// How many right rear tires used so far.
+pub const RR_TIRES_USED: Var<i32> =
+    Var::new("RRTiresUsed", "", "How many right rear tires used so far");
+
+/// How many left tire sets used so far.
+pub const LEFT_TIRE_SETS_USED: Var<i32> = Var::new(
+    "LeftTireSetsUsed",
+    "",
+    "How many left tire sets used so far",
+);
+
+/// How many right tire sets used so far.
+pub const RIGHT_TIRE_SETS_USED: Var<i32> = Var::new(
+    "RightTireSetsUsed",
+    "",
+    "How many right tire sets used so far",
+);
+
+/// How many front tire sets used so far.
+pub const FRONT_TIRE_SETS_USED: Var<i32> = Var::new(
+    "FrontTireSetsUsed",
+    "",
+    "How many front tire sets used so far",
+);
+
+/// How many rear tire sets used so far.
+pub const REAR_TIRE_SETS_USED: Var<i32> = Var::new(
+    "RearTireSetsUsed",
+    "",
+    "How many rear tire sets used so far",
+);
+
+/// How many tire sets used so far.
+pub const TIRE_SETS_USED: Var<i32> = Var::new("TireSetsUsed", "", "How many tire sets used so far");
+
+/// How many left front tires are remaining, 255 is unlimited.
+pub const LF_TIRES_AVAILABLE: Var<i32> = Var::new(
+    "LFTiresAvailable",
+    "",
+    "How many left front tires are remaining, 255 is unlimited",
+);
+
+/// How many right front tires are remaining, 255 is unlimited.
+pub const RF_TIRES_AVAILABLE: Var<i32> = Var::new(
+    "RFTiresAvailable",
+    "",
+    "How many right front tires are remaining, 255 is unlimited",
+);
+
+/// How many left rear tires are remaining, 255 is unlimited.
+pub const LR_TIRES_AVAILABLE: Var<i32> = Var::new(
+    "LRTiresAvailable",
+    "",
+    "How many left rear tires are remaining, 255 is unlimited",
+);
+
+/// How many right rear tires are remaining, 255 is unlimited.
+pub const RR_TIRES_AVAILABLE: Var<i32> = Var::new(
+    "RRTiresAvailable",
+    "",
+    "How many right rear tires are remaining, 255 is unlimited",
+);
+
+/// How many left tire sets are remaining, 255 is unlimited.
+pub const LEFT_TIRE_SETS_AVAILABLE: Var<i32> = Var::new(
+    "LeftTireSetsAvailable",
+    "",
+    "How many left tire sets are remaining, 255 is unlimited",
+);
+
+/// How many right tire sets are remaining, 255 is unlimited.
+pub const RIGHT_TIRE_SETS_AVAILABLE: Var<i32> = Var::new(
+    "RightTireSetsAvailable",
+    "",
+    "How many right tire sets are remaining, 255 is unlimited",
+);
+
+/// How many front tire sets are remaining, 255 is unlimited.
+pub const FRONT_TIRE_SETS_AVAILABLE: Var<i32> = Var::new(
+    "FrontTireSetsAvailable",
+    "",
+    "How many front tire sets are remaining, 255 is unlimited",
+);
+
+/// How many rear tire sets are remaining, 255 is unlimited.
+pub const REAR_TIRE_SETS_AVAILABLE: Var<i32> = Var::new(
+    "RearTireSetsAvailable",
+    "",
+    "How many rear tire sets are remaining, 255 is unlimited",
+);
+
+/// How many tire sets are remaining, 255 is unlimited.
+pub const TIRE_SETS_AVAILABLE: Var<i32> = Var::new(
+    "TireSetsAvailable",
+    "",
+    "How many tire sets are remaining, 255 is unlimited",
+);
+
+/// Active camera's focus car index.
+pub const CAM_CAR_IDX: Var<i32> = Var::new("CamCarIdx", "", "Active camera's focus car index");
+
+/// Active camera number.
+pub const CAM_CAMERA_NUMBER: Var<i32> = Var::new("CamCameraNumber", "", "Active camera number");
+
+/// Active camera group number.
+pub const CAM_GROUP_NUMBER: Var<i32> = Var::new("CamGroupNumber", "", "Active camera group number");
+
+/// State of camera system.
+pub const CAM_CAMERA_STATE: Var<CameraState> = Var::new(
+    "CamCameraState",
+    "irsdk_CameraState",
+    "State of camera system",
+);
+
+/// 1=Car on track physics running.
+pub const IS_ON_TRACK_CAR: Var<bool> =
+    Var::new("IsOnTrackCar", "", "1=Car on track physics running");
+
+/// 1=Car in garage physics running.
+pub const IS_IN_GARAGE: Var<bool> = Var::new("IsInGarage", "", "1=Car in garage physics running");
+
+/// Steering wheel max angle.
+pub const STEERING_WHEEL_ANGLE_MAX: Var<f32> =
+    Var::new("SteeringWheelAngleMax", "rad", "Steering wheel max angle");
+
+/// Friction torque applied to gears when shifting or grinding.
+pub const SHIFT_POWER_PCT: Var<f32> = Var::new(
+    "ShiftPowerPct",
+    "%",
+    "Friction torque applied to gears when shifting or grinding",
+);
+
+/// RPM of shifter grinding noise.
+pub const SHIFT_GRIND_RPM: Var<f32> =
+    Var::new("ShiftGrindRPM", "RPM", "RPM of shifter grinding noise");
+
+/// Raw throttle input 0=off throttle to 1=full throttle.
+pub const THROTTLE_RAW: Var<f32> = Var::new(
+    "ThrottleRaw",
+    "%",
+    "Raw throttle input 0=off throttle to 1=full throttle",
+);
+
+/// Raw brake input 0=brake released to 1=max pedal force.
+pub const BRAKE_RAW: Var<f32> = Var::new(
+    "BrakeRaw",
+    "%",
+    "Raw brake input 0=brake released to 1=max pedal force",
+);
+
+/// Raw clutch input 0=disengaged to 1=fully engaged.
+pub const CLUTCH_RAW: Var<f32> = Var::new(
+    "ClutchRaw",
+    "%",
+    "Raw clutch input 0=disengaged to 1=fully engaged",
+);
+
+/// Raw handbrake input 0=handbrake released to 1=max force.
+pub const HANDBRAKE_RAW: Var<f32> = Var::new(
+    "HandbrakeRaw",
+    "%",
+    "Raw handbrake input 0=handbrake released to 1=max force",
+);
+
+/// True if abs is currently reducing brake force pressure.
+pub const BRAKE_ABS_ACTIVE: Var<bool> = Var::new(
+    "BrakeABSactive",
+    "",
+    "true if abs is currently reducing brake force pressure",
+);
+
+/// Bitfield for warning lights.
+pub const ENGINE_WARNINGS: Var<EngineWarnings> = Var::new(
+    "EngineWarnings",
+    "irsdk_EngineWarnings",
+    "Bitfield for warning lights",
+);
+
+/// Percent fuel remaining.
+pub const FUEL_LEVEL_PCT: Var<f32> = Var::new("FuelLevelPct", "%", "Percent fuel remaining");
+
+/// Bitfield of pit service checkboxes.
+pub const PIT_SV_FLAGS: Var<PitServiceFlags> = Var::new(
+    "PitSvFlags",
+    "irsdk_PitSvFlags",
+    "Bitfield of pit service checkboxes",
+);
+
+/// Pit service left front tire pressure.
+pub const PIT_SV_LFP: Var<f32> =
+    Var::new("PitSvLFP", "kPa", "Pit service left front tire pressure");
+
+/// Pit service right front tire pressure.
+pub const PIT_SV_RFP: Var<f32> =
+    Var::new("PitSvRFP", "kPa", "Pit service right front tire pressure");
+
+/// Pit service left rear tire pressure.
+pub const PIT_SV_LRP: Var<f32> = Var::new("PitSvLRP", "kPa", "Pit service left rear tire pressure");
+
+/// Pit service right rear tire pressure.
+pub const PIT_SV_RRP: Var<f32> =
+    Var::new("PitSvRRP", "kPa", "Pit service right rear tire pressure");
+
+/// Pit service fuel add amount.
+pub const PIT_SV_FUEL: Var<f32> = Var::new("PitSvFuel", "l or kWh", "Pit service fuel add amount");
+
+/// Pit service pending tire compound.
+pub const PIT_SV_TIRE_COMPOUND: Var<i32> =
+    Var::new("PitSvTireCompound", "", "Pit service pending tire compound");
+
+/// Push2Pass active or not.
+pub const CAR_IDX_P2P_STATUS: VarArray<bool> =
+    VarArray::new("CarIdxP2P_Status", "", "Push2Pass active or not");
+
+/// Push2Pass count of usage (or remaining in Race).
+pub const CAR_IDX_P2P_COUNT: VarArray<i32> = VarArray::new(
+    "CarIdxP2P_Count",
+    "",
+    "Push2Pass count of usage (or remaining in Race)",
+);
+
+/// Force feedback % max torque on steering shaft unsigned.
+pub const STEERING_WHEEL_PCT_TORQUE: Var<f32> = Var::new(
+    "SteeringWheelPctTorque",
+    "%",
+    "Force feedback % max torque on steering shaft unsigned",
+);
+
+/// Force feedback % max torque on steering shaft signed.
+pub const STEERING_WHEEL_PCT_TORQUE_SIGN: Var<f32> = Var::new(
+    "SteeringWheelPctTorqueSign",
+    "%",
+    "Force feedback % max torque on steering shaft signed",
+);
+
+/// Force feedback % max torque on steering shaft signed stops.
+pub const STEERING_WHEEL_PCT_TORQUE_SIGN_STOPS: Var<f32> = Var::new(
+    "SteeringWheelPctTorqueSignStops",
+    "%",
+    "Force feedback % max torque on steering shaft signed stops",
+);
+
+/// Force feedback % max intensity.
+pub const STEERING_WHEEL_PCT_INTENSITY: Var<f32> = Var::new(
+    "SteeringWheelPctIntensity",
+    "%",
+    "Force feedback % max intensity",
+);
+
+/// Force feedback % max smoothing.
+pub const STEERING_WHEEL_PCT_SMOOTHING: Var<f32> = Var::new(
+    "SteeringWheelPctSmoothing",
+    "%",
+    "Force feedback % max smoothing",
+);
+
+/// Force feedback % max damping.
+pub const STEERING_WHEEL_PCT_DAMPER: Var<f32> = Var::new(
+    "SteeringWheelPctDamper",
+    "%",
+    "Force feedback % max damping",
+);
+
+/// Force feedback limiter strength limits impacts and oscillation.
+pub const STEERING_WHEEL_LIMITER: Var<f32> = Var::new(
+    "SteeringWheelLimiter",
+    "%",
+    "Force feedback limiter strength limits impacts and oscillation",
+);
+
+/// Value of strength or max force slider in Nm for FFB.
+pub const STEERING_WHEEL_MAX_FORCE_NM: Var<f32> = Var::new(
+    "SteeringWheelMaxForceNm",
+    "N*m",
+    "Value of strength or max force slider in Nm for FFB",
+);
+
+/// Peak torque mapping to direct input units for FFB.
+pub const STEERING_WHEEL_PEAK_FORCE_NM: Var<f32> = Var::new(
+    "SteeringWheelPeakForceNm",
+    "N*m",
+    "Peak torque mapping to direct input units for FFB",
+);
+
+/// True if steering wheel force is using linear mode.
+pub const STEERING_WHEEL_USE_LINEAR: Var<bool> = Var::new(
+    "SteeringWheelUseLinear",
+    "",
+    "True if steering wheel force is using linear mode",
+);
+
+/// DEPRECATED use DriverCarSLBlinkRPM instead.
+pub const SHIFT_INDICATOR_PCT: Var<f32> = Var::new(
+    "ShiftIndicatorPct",
+    "%",
+    "DEPRECATED use DriverCarSLBlinkRPM instead",
+);
+
+/// Replay playback speed.
+pub const REPLAY_PLAY_SPEED: Var<i32> = Var::new("ReplayPlaySpeed", "", "Replay playback speed");
+
+/// 0=not slow motion, 1=replay is in slow motion.
+pub const REPLAY_PLAY_SLOW_MOTION: Var<bool> = Var::new(
+    "ReplayPlaySlowMotion",
+    "",
+    "0=not slow motion, 1=replay is in slow motion",
+);
+
+/// Seconds since replay session start.
+pub const REPLAY_SESSION_TIME: Var<f64> = Var::new(
+    "ReplaySessionTime",
+    "s",
+    "Seconds since replay session start",
+);
+
+/// Replay session number.
+pub const REPLAY_SESSION_NUM: Var<i32> = Var::new("ReplaySessionNum", "", "Replay session number");
+
+/// Players LF Tire Sound rumblestrip pitch.
+pub const TIRE_LF_RUMBLE_PITCH: Var<f32> = Var::new(
+    "TireLF_RumblePitch",
+    "Hz",
+    "Players LF Tire Sound rumblestrip pitch",
+);
+
+/// Players RF Tire Sound rumblestrip pitch.
+pub const TIRE_RF_RUMBLE_PITCH: Var<f32> = Var::new(
+    "TireRF_RumblePitch",
+    "Hz",
+    "Players RF Tire Sound rumblestrip pitch",
+);
+
+/// Players LR Tire Sound rumblestrip pitch.
+pub const TIRE_LR_RUMBLE_PITCH: Var<f32> = Var::new(
+    "TireLR_RumblePitch",
+    "Hz",
+    "Players LR Tire Sound rumblestrip pitch",
+);
+
+/// Players RR Tire Sound rumblestrip pitch.
+pub const TIRE_RR_RUMBLE_PITCH: Var<f32> = Var::new(
+    "TireRR_RumblePitch",
+    "Hz",
+    "Players RR Tire Sound rumblestrip pitch",
+);
+
+/// 1=Garage screen is visible.
+pub const IS_GARAGE_VISIBLE: Var<bool> =
+    Var::new("IsGarageVisible", "", "1=Garage screen is visible");
+
+/// Output torque on steering shaft at 360 Hz.
+pub const STEERING_WHEEL_TORQUE_ST: VarArray<f32> = VarArray::new(
+    "SteeringWheelTorque_ST",
+    "N*m",
+    "Output torque on steering shaft at 360 Hz",
+);
+
+/// Output torque on steering shaft.
+pub const STEERING_WHEEL_TORQUE: Var<f32> = Var::new(
+    "SteeringWheelTorque",
+    "N*m",
+    "Output torque on steering shaft",
+);
+
+/// Z velocity.
+pub const VELOCITY_Z_ST: VarArray<f32> =
+    VarArray::new("VelocityZ_ST", "m/s at 360 Hz", "Z velocity");
+
+/// Y velocity.
+pub const VELOCITY_Y_ST: VarArray<f32> =
+    VarArray::new("VelocityY_ST", "m/s at 360 Hz", "Y velocity");
+
+/// X velocity.
+pub const VELOCITY_X_ST: VarArray<f32> =
+    VarArray::new("VelocityX_ST", "m/s at 360 Hz", "X velocity");
+
+/// Z velocity.
+pub const VELOCITY_Z: Var<f32> = Var::new("VelocityZ", "m/s", "Z velocity");
+
+/// Y velocity.
+pub const VELOCITY_Y: Var<f32> = Var::new("VelocityY", "m/s", "Y velocity");
+
+/// X velocity.
+pub const VELOCITY_X: Var<f32> = Var::new("VelocityX", "m/s", "X velocity");
+
+/// Yaw rate at 360 Hz.
+pub const YAW_RATE_ST: VarArray<f32> = VarArray::new("YawRate_ST", "rad/s", "Yaw rate at 360 Hz");
+
+/// Pitch rate at 360 Hz.
+pub const PITCH_RATE_ST: VarArray<f32> =
+    VarArray::new("PitchRate_ST", "rad/s", "Pitch rate at 360 Hz");
+
+/// Roll rate at 360 Hz.
+pub const ROLL_RATE_ST: VarArray<f32> =
+    VarArray::new("RollRate_ST", "rad/s", "Roll rate at 360 Hz");
+
+/// Yaw rate.
+pub const YAW_RATE: Var<f32> = Var::new("YawRate", "rad/s", "Yaw rate");
+
+/// Pitch rate.
+pub const PITCH_RATE: Var<f32> = Var::new("PitchRate", "rad/s", "Pitch rate");
+
+/// Roll rate.
+pub const ROLL_RATE: Var<f32> = Var::new("RollRate", "rad/s", "Roll rate");
+
+/// Vertical acceleration (including gravity) at 360 Hz.
+pub const VERT_ACCEL_ST: VarArray<f32> = VarArray::new(
+    "VertAccel_ST",
+    "m/s^2",
+    "Vertical acceleration (including gravity) at 360 Hz",
+);
+
+/// Lateral acceleration (including gravity) at 360 Hz.
+pub const LAT_ACCEL_ST: VarArray<f32> = VarArray::new(
+    "LatAccel_ST",
+    "m/s^2",
+    "Lateral acceleration (including gravity) at 360 Hz",
+);
+
+/// Longitudinal acceleration (including gravity) at 360 Hz.
+pub const LONG_ACCEL_ST: VarArray<f32> = VarArray::new(
+    "LongAccel_ST",
+    "m/s^2",
+    "Longitudinal acceleration (including gravity) at 360 Hz",
+);
+
+/// Vertical acceleration (including gravity).
+pub const VERT_ACCEL: Var<f32> = Var::new(
+    "VertAccel",
+    "m/s^2",
+    "Vertical acceleration (including gravity)",
+);
+
+/// Lateral acceleration (including gravity).
+pub const LAT_ACCEL: Var<f32> = Var::new(
+    "LatAccel",
+    "m/s^2",
+    "Lateral acceleration (including gravity)",
+);
+
+/// Longitudinal acceleration (including gravity).
+pub const LONG_ACCEL: Var<f32> = Var::new(
+    "LongAccel",
+    "m/s^2",
+    "Longitudinal acceleration (including gravity)",
+);
+
+/// In car trigger car starter.
+pub const DC_STARTER: Var<bool> = Var::new("dcStarter", "", "In car trigger car starter");
+
+/// Pitstop right tire change request.
+pub const DP_R_TIRE_CHANGE: Var<f32> =
+    Var::new("dpRTireChange", "", "Pitstop right tire change request");
+
+/// Pitstop left tire change request.
+pub const DP_L_TIRE_CHANGE: Var<f32> =
+    Var::new("dpLTireChange", "", "Pitstop left tire change request");
+
+/// Pitstop fuel fill flag.
+pub const DP_FUEL_FILL: Var<f32> = Var::new("dpFuelFill", "", "Pitstop fuel fill flag");
+
+/// Pitstop auto fill fuel system enabled.
+pub const DP_FUEL_AUTO_FILL_ENABLED: Var<f32> = Var::new(
+    "dpFuelAutoFillEnabled",
+    "",
+    "Pitstop auto fill fuel system enabled",
+);
+
+/// Pitstop auto fill fuel next stop flag.
+pub const DP_FUEL_AUTO_FILL_ACTIVE: Var<f32> = Var::new(
+    "dpFuelAutoFillActive",
+    "",
+    "Pitstop auto fill fuel next stop flag",
+);
+
+/// Pitstop windshield tearoff.
+pub const DP_WINDSHIELD_TEAROFF: Var<f32> =
+    Var::new("dpWindshieldTearoff", "", "Pitstop windshield tearoff");
+
+/// Pitstop fuel add amount.
+pub const DP_FUEL_ADD_KG: Var<f32> = Var::new("dpFuelAddKg", "kg", "Pitstop fuel add amount");
+
+/// Pitstop fast repair set.
+pub const DP_FAST_REPAIR: Var<f32> = Var::new("dpFastRepair", "", "Pitstop fast repair set");
+
+/// In car brake bias adjustment.
+pub const DC_BRAKE_BIAS: Var<f32> = Var::new("dcBrakeBias", "", "In car brake bias adjustment");
+
+/// Pitstop lf tire cold pressure adjustment.
+pub const DP_LF_TIRE_COLD_PRESS: Var<f32> = Var::new(
+    "dpLFTireColdPress",
+    "Pa",
+    "Pitstop lf tire cold pressure adjustment",
+);
+
+/// Pitstop rf cold tire pressure adjustment.
+pub const DP_RF_TIRE_COLD_PRESS: Var<f32> = Var::new(
+    "dpRFTireColdPress",
+    "Pa",
+    "Pitstop rf cold tire pressure adjustment",
+);
+
+/// Pitstop lr tire cold pressure adjustment.
+pub const DP_LR_TIRE_COLD_PRESS: Var<f32> = Var::new(
+    "dpLRTireColdPress",
+    "Pa",
+    "Pitstop lr tire cold pressure adjustment",
+);
+
+/// Pitstop rr cold tire pressure adjustment.
+pub const DP_RR_TIRE_COLD_PRESS: Var<f32> = Var::new(
+    "dpRRTireColdPress",
+    "Pa",
+    "Pitstop rr cold tire pressure adjustment",
+);
+
+/// Pitstop left wedge/weight jacker adjustment.
+pub const DP_WEIGHT_JACKER_LEFT: Var<f32> = Var::new(
+    "dpWeightJackerLeft",
+    "",
+    "Pitstop left wedge/weight jacker adjustment",
+);
+
+/// Pitstop right wedge/weight jacker adjustment.
+pub const DP_WEIGHT_JACKER_RIGHT: Var<f32> = Var::new(
+    "dpWeightJackerRight",
+    "",
+    "Pitstop right wedge/weight jacker adjustment",
+);
+
+/// Engine fuel used instantaneous.
+pub const FUEL_USE_PER_HOUR: Var<f32> =
+    Var::new("FuelUsePerHour", "kg/h", "Engine fuel used instantaneous");
+
+/// Engine voltage.
+pub const VOLTAGE: Var<f32> = Var::new("Voltage", "V", "Engine voltage");
+
+/// Engine coolant temp.
+pub const WATER_TEMP: Var<f32> = Var::new("WaterTemp", "C", "Engine coolant temp");
+
+/// Engine coolant level.
+pub const WATER_LEVEL: Var<f32> = Var::new("WaterLevel", "l", "Engine coolant level");
+
+/// Engine fuel pressure.
+pub const FUEL_PRESS: Var<f32> = Var::new("FuelPress", "bar", "Engine fuel pressure");
+
+/// Engine oil temperature.
+pub const OIL_TEMP: Var<f32> = Var::new("OilTemp", "C", "Engine oil temperature");
+
+/// Engine oil pressure.
+pub const OIL_PRESS: Var<f32> = Var::new("OilPress", "bar", "Engine oil pressure");
+
+/// Engine oil level.
+pub const OIL_LEVEL: Var<f32> = Var::new("OilLevel", "l", "Engine oil level");
+
+/// Engine manifold pressure.
+pub const MANIFOLD_PRESS: Var<f32> = Var::new("ManifoldPress", "bar", "Engine manifold pressure");
+
+/// Liters of fuel remaining.
+pub const FUEL_LEVEL: Var<f32> = Var::new("FuelLevel", "l", "Liters of fuel remaining");
+
+/// RF tire cold pressure, as set in the garage.
+pub const RF_COLD_PRESSURE: Var<f32> = Var::new(
+    "RFcoldPressure",
+    "kPa",
+    "RF tire cold pressure, as set in the garage",
+);
+
+/// RF tire left carcass temperature.
+pub const RF_TEMP_CL: Var<f32> = Var::new("RFtempCL", "C", "RF tire left carcass temperature");
+
+/// RF tire middle carcass temperature.
+pub const RF_TEMP_CM: Var<f32> = Var::new("RFtempCM", "C", "RF tire middle carcass temperature");
+
+/// RF tire right carcass temperature.
+pub const RF_TEMP_CR: Var<f32> = Var::new("RFtempCR", "C", "RF tire right carcass temperature");
+
+/// RF tire left percent tread remaining.
+pub const RF_WEAR_L: Var<f32> = Var::new("RFwearL", "%", "RF tire left percent tread remaining");
+
+/// RF tire middle percent tread remaining.
+pub const RF_WEAR_M: Var<f32> = Var::new("RFwearM", "%", "RF tire middle percent tread remaining");
+
+/// RF tire right percent tread remaining.
+pub const RF_WEAR_R: Var<f32> = Var::new("RFwearR", "%", "RF tire right percent tread remaining");
+
+/// LF tire cold pressure, as set in the garage.
+pub const LF_COLD_PRESSURE: Var<f32> = Var::new(
+    "LFcoldPressure",
+    "kPa",
+    "LF tire cold pressure, as set in the garage",
+);
+
+/// LF tire left carcass temperature.
+pub const LF_TEMP_CL: Var<f32> = Var::new("LFtempCL", "C", "LF tire left carcass temperature");
+
+/// LF tire middle carcass temperature.
+pub const LF_TEMP_CM: Var<f32> = Var::new("LFtempCM", "C", "LF tire middle carcass temperature");
+
+/// LF tire right carcass temperature.
+pub const LF_TEMP_CR: Var<f32> = Var::new("LFtempCR", "C", "LF tire right carcass temperature");
+
+/// LF tire left percent tread remaining.
+pub const LF_WEAR_L: Var<f32> = Var::new("LFwearL", "%", "LF tire left percent tread remaining");
+
+/// LF tire middle percent tread remaining.
+pub const LF_WEAR_M: Var<f32> = Var::new("LFwearM", "%", "LF tire middle percent tread remaining");
+
+/// LF tire right percent tread remaining.
+pub const LF_WEAR_R: Var<f32> = Var::new("LFwearR", "%", "LF tire right percent tread remaining");
+
+/// RR tire cold pressure, as set in the garage.
+pub const RR_COLD_PRESSURE: Var<f32> = Var::new(
+    "RRcoldPressure",
+    "kPa",
+    "RR tire cold pressure, as set in the garage",
+);
+
+/// RR tire left carcass temperature.
+pub const RR_TEMP_CL: Var<f32> = Var::new("RRtempCL", "C", "RR tire left carcass temperature");
+
+/// RR tire middle carcass temperature.
+pub const RR_TEMP_CM: Var<f32> = Var::new("RRtempCM", "C", "RR tire middle carcass temperature");
+
+/// RR tire right carcass temperature.
+pub const RR_TEMP_CR: Var<f32> = Var::new("RRtempCR", "C", "RR tire right carcass temperature");
+
+/// RR tire left percent tread remaining.
+pub const RR_WEAR_L: Var<f32> = Var::new("RRwearL", "%", "RR tire left percent tread remaining");
+
+/// RR tire middle percent tread remaining.
+pub const RR_WEAR_M: Var<f32> = Var::new("RRwearM", "%", "RR tire middle percent tread remaining");
+
+/// RR tire right percent tread remaining.
+pub const RR_WEAR_R: Var<f32> = Var::new("RRwearR", "%", "RR tire right percent tread remaining");
+
+/// LR tire cold pressure, as set in the garage.
+pub const LR_COLD_PRESSURE: Var<f32> = Var::new(
+    "LRcoldPressure",
+    "kPa",
+    "LR tire cold pressure, as set in the garage",
+);
+
+/// LR tire left carcass temperature.
+pub const LR_TEMP_CL: Var<f32> = Var::new("LRtempCL", "C", "LR tire left carcass temperature");
+
+/// LR tire middle carcass temperature.
+pub const LR_TEMP_CM: Var<f32> = Var::new("LRtempCM", "C", "LR tire middle carcass temperature");
+
+/// LR tire right carcass temperature.
+pub const LR_TEMP_CR: Var<f32> = Var::new("LRtempCR", "C", "LR tire right carcass temperature");
+
+/// LR tire left percent tread remaining.
+pub const LR_WEAR_L: Var<f32> = Var::new("LRwearL", "%", "LR tire left percent tread remaining");
+
+/// LR tire middle percent tread remaining.
+pub const LR_WEAR_M: Var<f32> = Var::new("LRwearM", "%", "LR tire middle percent tread remaining");
+
+/// LR tire right percent tread remaining.
+pub const LR_WEAR_R: Var<f32> = Var::new("LRwearR", "%", "LR tire right percent tread remaining");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapped_file::dumpfile::DumpFile;
+    use crate::source::TelemetrySource;
+    use crate::synthetic::SyntheticBuilder;
+    use crate::units::Unit;
+
+    #[test]
+    fn test_keys_match_recorded_headers() {
+        let dump = DumpFile::open("tests/data/2024-08-03.dat").unwrap();
+        let headers = dump
+            .get_var_headers()
+            .unwrap()
+            .into_iter()
+            .map(|h| (h.name.clone(), h))
+            .collect::<HashMap<_, _>>();
+
+        assert!(SPEED.resolve(&headers).is_some());
+        assert!(SESSION_FLAGS.resolve(&headers).is_some());
+        assert!(CAR_IDX_LAP_DIST_PCT.resolve(&headers).is_some());
+        assert!(CAR_IDX_TRACK_SURFACE.resolve(&headers).is_some());
+        // Wrong type or count.
+        assert!(Var::<f32>::new("Gear", "", "").resolve(&headers).is_none());
+        assert!(Var::<f32>::new("CarIdxLapDistPct", "", "")
+            .resolve(&headers)
+            .is_none());
+        assert_eq!(SPEED.unit(), "m/s");
+        assert_eq!(
+            Unit::parse(VERT_ACCEL.unit()),
+            Some(Unit::MetersPerSecondSquared)
+        );
+        assert_eq!(
+            Unit::parse(FUEL_USE_PER_HOUR.unit()),
+            Some(Unit::KilogramsPerHour)
+        );
+        assert_eq!(
+            Unit::parse(STEERING_WHEEL_MAX_FORCE_NM.unit()),
+            Some(Unit::NewtonMeters)
+        );
+    }
+
+    #[test]
+    fn test_lap_times_are_durations() {
+        let telemetry = SyntheticBuilder::new(60)
+            .var("LapLastLapTime", "s", "Players last lap time", |_| -1f32)
+            .var("LapCurrentLapTime", "s", "Current lap time", |_| 12.5f32)
+            .array("CarIdxBestLapTime", 3, "s", "Cars best lap time", |_, i| {
+                [-1., 90.5, -1.][i]
+            })
+            .build();
+        let data = telemetry.data_at(0);
+        assert_eq!(data.read(&LAP_LAST_LAP_TIME), Some(None));
+        assert_eq!(
+            data.read(&LAP_CURRENT_LAP_TIME),
+            Some(Duration::from_millis(12_500))
+        );
+        assert_eq!(
+            data.read(&CAR_IDX_BEST_LAP_TIME),
+            Some(vec![None, Some(Duration::from_millis(90_500)), None])
+        );
+        let best = CAR_IDX_BEST_LAP_TIME
+            .handle(&telemetry.var_headers())
+            .unwrap();
+        assert_eq!(best.read_at(&data, 2).unwrap(), None);
+    }
+}
@@ -0,0 +1,155 @@
+use ir_telemetry::units::Unit;
+use ir_telemetry::{
+    vars, Client, DumpFile, IrError, Recording, Session, TelemetrySource, UpdatePacket,
+};
+
+const DUMP_PATH: &str = "tests/data/2024-08-03.dat";
+const HEADER_BUFFERS_OFFSET: usize = 48;
+const BUFFER_SIZE: usize = 16;
+
+/// The dump in `tests/data` was created before the dump contained the var buffers.  This appends a buffer row and
+/// points the header at it, with `Speed` set to the given value.
+fn complete_dump(speed: f32) -> Vec<u8> {
+    let mut bytes = std::fs::read(DUMP_PATH).unwrap();
+    let dump = DumpFile::from_bytes(bytes.clone()).unwrap();
+    let header = dump.header().unwrap();
+    let speed_header = dump
+        .get_var_headers()
+        .unwrap()
+        .into_iter()
+        .find(|h| h.name() == "Speed")
+        .unwrap();
+
+    let buf_len = i32::from_le_bytes(bytes[36..40].try_into().unwrap()) as usize;
+    let num_buf = i32::from_le_bytes(bytes[32..36].try_into().unwrap()) as usize;
+    let row_offset = bytes.len();
+    let mut row = vec![0u8; buf_len];
+    let speed_offset = speed_header.offset() as usize;
+    row[speed_offset..speed_offset + 4].copy_from_slice(&speed.to_le_bytes());
+    bytes.extend_from_slice(&row);
+
+    for i in 0..num_buf {
+        let offset = HEADER_BUFFERS_OFFSET + i * BUFFER_SIZE + 4;
+        bytes[offset..offset + 4].copy_from_slice(&(row_offset as i32).to_le_bytes());
+    }
+    assert!(header.is_connected());
+    bytes
+}
+
+#[test]
+fn reads_var_headers_and_session_info() {
+    let dump = DumpFile::open(DUMP_PATH).unwrap();
+    let headers = dump.get_var_headers().unwrap();
+    assert_eq!(headers.len(), 310);
+    let speed = headers.iter().find(|h| h.name() == "Speed").unwrap();
+    assert_eq!(speed.units(), "m/s");
+    assert_eq!(speed.unit_type(), Some(Unit::MetersPerSecond));
+    // The last variable in the file.
+    let last = headers.last().unwrap();
+    assert_eq!(last.name(), "RFSHshockVel_ST");
+    assert!(last.counts_as_time() && !last.is_time());
+    let lap_time = headers
+        .iter()
+        .find(|h| h.name() == "LapLastLapTime")
+        .unwrap();
+    assert!(lap_time.is_time() && !lap_time.counts_as_time());
+
+    let session: Session = serde_yaml::from_str(&dump.session_info().unwrap()).unwrap();
+    assert_eq!(session.weekend_info.track_name, "limerock 2019 classic");
+    let track_length = session.weekend_info.track_length.to(Unit::Miles).unwrap();
+    assert!((track_length.value - 1.46).abs() < 0.01);
+    let pit_speed_limit = session
+        .weekend_info
+        .track_pit_speed_limit
+        .to(Unit::MilesPerHour);
+    assert!((pit_speed_limit.unwrap().value - 35.).abs() < 0.01);
+}
+
+#[test]
+fn truncated_dump_reports_out_of_bounds() {
+    let dump = DumpFile::open(DUMP_PATH).unwrap();
+    assert!(matches!(
+        dump.get_new_data(),
+        Err(IrError::OutOfBounds { .. })
+    ));
+}
+
+#[test]
+fn with_latest_views_the_dump_buffer() {
+    let dump = DumpFile::from_bytes(complete_dump(42.5)).unwrap();
+    let var_headers = dump
+        .get_var_headers()
+        .unwrap()
+        .into_iter()
+        .map(|h| (h.name().to_string(), h))
+        .collect();
+    let speed = vars::SPEED.handle(&var_headers).unwrap();
+    let data = dump.get_new_data().unwrap();
+    let (view_tick, view_speed) = dump
+        .with_latest(|view| (view.tick(), view.read(&speed).unwrap()))
+        .unwrap();
+    assert_eq!(view_tick, data.tick());
+    assert_eq!(view_speed, 42.5);
+    assert_eq!(
+        dump.with_latest(|view| view.data().to_vec()).unwrap(),
+        data.data()
+    );
+
+    let truncated = DumpFile::open(DUMP_PATH).unwrap();
+    assert!(matches!(
+        truncated.with_latest(|view| view.tick()),
+        Err(IrError::OutOfBounds { .. })
+    ));
+}
+
+#[test]
+fn replayed_dump_data_has_var_headers() {
+    let dump = DumpFile::from_bytes(complete_dump(42.5)).unwrap();
+    let (rx, _handle) = Client::replay_recording(Recording::Dump(dump), 1.).unwrap();
+    let data = rx
+        .iter()
+        .find_map(|packet| match packet {
+            UpdatePacket::Data(data) => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(data.try_get::<f32>("Speed").unwrap(), 42.5);
+    assert_eq!(data.session_time(), Some(0.));
+}
+
+#[test]
+fn corrupt_dump_is_rejected() {
+    let mut bytes = std::fs::read(DUMP_PATH).unwrap();
+    // Number of buffers
+    bytes[32..36].copy_from_slice(&9i32.to_le_bytes());
+    assert!(matches!(
+        DumpFile::from_bytes(bytes),
+        Err(IrError::InvalidHeader(_))
+    ));
+}
+
+#[test]
+fn client_sends_packets_from_dump() {
+    let bytes = complete_dump(42.5);
+    let rx = Client::connect_with(60., move || DumpFile::from_bytes(bytes.clone()));
+
+    let mut var_headers = None;
+    let mut session_info = None;
+    let mut session = None;
+    for packet in rx {
+        match packet {
+            UpdatePacket::Connected { tick_rate, .. } => assert_eq!(tick_rate, 60),
+            UpdatePacket::SessionChanged { sub_session_id, .. } => session = Some(sub_session_id),
+            UpdatePacket::VariableHeaders(headers) => var_headers = Some(headers),
+            UpdatePacket::SessionInfo(session) => session_info = Some(session),
+            UpdatePacket::Data(data) => {
+                let headers = var_headers.as_ref().expect("Headers are sent first");
+                assert_eq!(data.get::<f32>(headers.get("Speed")), Some(42.5));
+                break;
+            }
+            _ => panic!("Unexpected packet: {:?}", packet),
+        }
+    }
+    assert!(session_info.is_some());
+    assert_eq!(session, Some(70540554));
+}